@@ -1,5 +1,6 @@
 use std::fmt;
 use crossterm::style::{Color, Stylize};
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Suit {
@@ -26,6 +27,136 @@ pub enum Rank {
     King = 13,
 }
 
+impl Suit {
+    // Also the order foundations are laid out in, one suit per slot
+    pub const ALL: [Suit; 4] = [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades];
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Suit::Hearts => "♥",
+            Suit::Diamonds => "♦",
+            Suit::Clubs => "♣",
+            Suit::Spades => "♠",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Suit::Hearts => "Hearts",
+            Suit::Diamonds => "Diamonds",
+            Suit::Clubs => "Clubs",
+            Suit::Spades => "Spades",
+        }
+    }
+
+    // Plain-text stand-in for terminals without the suit glyphs
+    pub fn letter(&self) -> &'static str {
+        match self {
+            Suit::Hearts => "H",
+            Suit::Diamonds => "D",
+            Suit::Clubs => "C",
+            Suit::Spades => "S",
+        }
+    }
+}
+
+impl Rank {
+    pub fn from_value(value: u8) -> Option<Rank> {
+        match value {
+            1 => Some(Rank::Ace),
+            2 => Some(Rank::Two),
+            3 => Some(Rank::Three),
+            4 => Some(Rank::Four),
+            5 => Some(Rank::Five),
+            6 => Some(Rank::Six),
+            7 => Some(Rank::Seven),
+            8 => Some(Rank::Eight),
+            9 => Some(Rank::Nine),
+            10 => Some(Rank::Ten),
+            11 => Some(Rank::Jack),
+            12 => Some(Rank::Queen),
+            13 => Some(Rank::King),
+            _ => None,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Rank::Ace => "A",
+            Rank::Two => "2",
+            Rank::Three => "3",
+            Rank::Four => "4",
+            Rank::Five => "5",
+            Rank::Six => "6",
+            Rank::Seven => "7",
+            Rank::Eight => "8",
+            Rank::Nine => "9",
+            Rank::Ten => "10",
+            Rank::Jack => "J",
+            Rank::Queen => "Q",
+            Rank::King => "K",
+        }
+    }
+}
+
+// Artwork for face-down cards. Every style is the same width so columns stay aligned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CardBack {
+    #[default]
+    Classic,
+    Shaded,
+    Hash,
+    Dots,
+}
+
+impl CardBack {
+    // Pattern inside the brackets, two characters wide
+    pub fn pattern(&self) -> &'static str {
+        match self {
+            CardBack::Classic => "??",
+            CardBack::Shaded => "▒▒",
+            CardBack::Hash => "##",
+            CardBack::Dots => "::",
+        }
+    }
+
+    pub fn compact(&self) -> String {
+        format!("[{}]", self.pattern())
+    }
+}
+
+// Colour for each suit, neon by default
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SuitPalette {
+    pub hearts: Color,
+    pub diamonds: Color,
+    pub clubs: Color,
+    pub spades: Color,
+}
+
+impl Default for SuitPalette {
+    fn default() -> Self {
+        SuitPalette {
+            hearts: Color::Rgb { r: 255, g: 50, b: 100 },   // Neon Pink
+            diamonds: Color::Rgb { r: 100, g: 200, b: 255 }, // Neon Blue
+            clubs: Color::Rgb { r: 150, g: 255, b: 150 },    // Neon Green
+            spades: Color::Rgb { r: 255, g: 255, b: 100 },   // Neon Yellow
+        }
+    }
+}
+
+impl SuitPalette {
+    pub fn color(&self, suit: Suit) -> Color {
+        match suit {
+            Suit::Hearts => self.hearts,
+            Suit::Diamonds => self.diamonds,
+            Suit::Clubs => self.clubs,
+            Suit::Spades => self.spades,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Card {
     pub suit: Suit,
@@ -62,17 +193,19 @@ impl Card {
     }
 
     pub fn get_color(&self) -> Color {
-        match self.suit {
-            Suit::Hearts => Color::Rgb { r: 255, g: 50, b: 100 },   // Neon Pink
-            Suit::Diamonds => Color::Rgb { r: 100, g: 200, b: 255 }, // Neon Blue
-            Suit::Clubs => Color::Rgb { r: 150, g: 255, b: 150 },    // Neon Green
-            Suit::Spades => Color::Rgb { r: 255, g: 255, b: 100 },   // Neon Yellow
-        }
+        SuitPalette::default().color(self.suit)
     }
 
     pub fn to_string_colored(&self) -> String {
+        self.to_string_colored_with(CardBack::default(), &SuitPalette::default())
+    }
+
+    pub fn to_string_colored_with(&self, back: CardBack, palette: &SuitPalette) -> String {
         if !self.face_up {
-            return format!("{}", "╭─────╮\n│ ??? │\n╰─────╯".with(Color::Rgb { r: 100, g: 100, b: 150 }));
+            // Three pattern characters fill the same space as "???" did
+            let pattern = back.pattern().chars().next().map_or(String::new(), |c| c.to_string().repeat(3));
+            let art = format!("╭─────╮\n│ {} │\n╰─────╯", pattern);
+            return format!("{}", art.with(Color::Rgb { r: 100, g: 100, b: 150 }));
         }
 
         let rank_str = match self.rank {
@@ -98,7 +231,7 @@ impl Card {
             Suit::Spades => "♠",
         };
 
-        let color = self.get_color();
+        let color = palette.color(self.suit);
         
         format!(
             "{}\n{} {}\n{}",
@@ -109,9 +242,51 @@ impl Card {
         )
     }
 
+    // Two-character code for position strings: the rank (A, 2-9, T, J, Q, K) then the
+    // suit letter, in capitals for a face-up card and lower case for a face-down one
+    pub fn code(&self) -> String {
+        let rank = match self.rank {
+            Rank::Ten => "T",
+            rank => rank.symbol(),
+        };
+        let code = format!("{}{}", rank, self.suit.letter());
+        if self.face_up { code } else { code.to_lowercase() }
+    }
+
+    // Reads a code made by code(); the suit letter's case says which way up the card is
+    pub fn from_code(code: &str) -> Option<Card> {
+        let mut chars = code.chars();
+        let (rank, suit) = (chars.next()?, chars.next()?);
+        if chars.next().is_some() {
+            return None;
+        }
+        let rank = match rank.to_ascii_uppercase() {
+            'A' => Rank::Ace,
+            'T' => Rank::Ten,
+            'J' => Rank::Jack,
+            'Q' => Rank::Queen,
+            'K' => Rank::King,
+            digit @ '2'..='9' => Rank::from_value(digit as u8 - b'0')?,
+            _ => return None,
+        };
+        let face_up = suit.is_ascii_uppercase();
+        let suit = match suit.to_ascii_uppercase() {
+            'H' => Suit::Hearts,
+            'D' => Suit::Diamonds,
+            'C' => Suit::Clubs,
+            'S' => Suit::Spades,
+            _ => return None,
+        };
+        Some(Card { face_up, ..Card::new(suit, rank) })
+    }
+
     pub fn to_string_compact(&self) -> String {
+        self.to_string_compact_with(CardBack::default())
+    }
+
+    pub fn to_string_compact_with(&self, back: CardBack) -> String {
         if !self.face_up {
-            return "[??]".to_string();
+            return back.compact();
         }
 
         let rank_str = match self.rank {
@@ -150,24 +325,9 @@ impl fmt::Display for Card {
 pub fn create_standard_deck() -> Vec<Card> {
     let mut deck = Vec::with_capacity(52);
     
-    for &suit in &[Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades] {
+    for suit in Suit::ALL {
         for rank_val in 1..=13 {
-            let rank = match rank_val {
-                1 => Rank::Ace,
-                2 => Rank::Two,
-                3 => Rank::Three,
-                4 => Rank::Four,
-                5 => Rank::Five,
-                6 => Rank::Six,
-                7 => Rank::Seven,
-                8 => Rank::Eight,
-                9 => Rank::Nine,
-                10 => Rank::Ten,
-                11 => Rank::Jack,
-                12 => Rank::Queen,
-                13 => Rank::King,
-                _ => unreachable!(),
-            };
+            let rank = Rank::from_value(rank_val).unwrap();
             deck.push(Card::new(suit, rank));
         }
     }
@@ -1,16 +1,189 @@
-use crate::game::{GameState, PileType};
-use crate::card::Card;
+use crate::game::{GameState, PileType, UndoKind};
+use crate::card::{Card, CardBack, Suit, SuitPalette};
+use crate::rules::ScoringMode;
+use crate::i18n::{Strings, ENGLISH};
+use crate::input::{convert_mouse_to_game_position, KEY_REFERENCE, RACE_KEYS};
+use crate::layout::Layout;
+use crate::moves::{draw_odds, find_valid_moves, hint_moves, move_between, Move, MoveLocation};
+use crate::race::{Race, BOARD_WIDTH, MIN_HEIGHT, MIN_WIDTH};
+use crate::stats::Stats;
 use crossterm::{
     cursor::{Hide, MoveTo, Show},
     execute,
-    style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
+    style::{Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor},
+    Command,
     terminal::{Clear, ClearType, EnableLineWrap, DisableLineWrap},
 };
+use std::fmt;
 use std::io::{stdout, Write};
 
+const SELECTED_BG: Color = Color::Rgb { r: 100, g: 0, b: 100 };
+const SELECTED_PULSE_BG: Color = Color::Rgb { r: 170, g: 0, b: 170 };
+const HINT_SOURCE_BG: Color = Color::Rgb { r: 0, g: 90, b: 110 };
+const HINT_TARGET_BG: Color = Color::Rgb { r: 0, g: 110, b: 40 };
+const JUST_DRAWN_TOP_BG: Color = Color::Rgb { r: 110, g: 80, b: 0 };
+const JUST_DRAWN_BG: Color = Color::Rgb { r: 55, g: 40, b: 0 };
+const FOCUS_BG: Color = Color::Rgb { r: 60, g: 60, b: 90 };
+const DEBUG_FG: Color = Color::Rgb { r: 120, g: 120, b: 120 };
+
+// Legal moves panel: where it starts, and how many moves fit below its title
+const MOVE_LIST_TOP: u16 = 9;
+const MOVE_LIST_ROWS: usize = 12;
+const MOVE_LIST_WIDTH: usize = 24;
+const WASTE_LIST_WIDTH: usize = 18;
+
+// A colour change that respects monochrome mode: foreground colours are dropped
+// and highlight backgrounds become reverse video, so no colour codes are sent
+pub struct Paint {
+    color: Color,
+    background: bool,
+    monochrome: bool,
+}
+
+impl Command for Paint {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        match (self.monochrome, self.background) {
+            (false, false) => SetForegroundColor(self.color).write_ansi(f),
+            (false, true) => SetBackgroundColor(self.color).write_ansi(f),
+            (true, true) => SetAttribute(Attribute::Reverse).write_ansi(f),
+            (true, false) => Ok(()),
+        }
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        match (self.monochrome, self.background) {
+            (false, false) => SetForegroundColor(self.color).execute_winapi(),
+            (false, true) => SetBackgroundColor(self.color).execute_winapi(),
+            (true, true) => SetAttribute(Attribute::Reverse).execute_winapi(),
+            (true, false) => Ok(()),
+        }
+    }
+}
+
+// How many colours the terminal can show. Everything is drawn in RGB, so
+// terminals without 24-bit colour get each shade's nearest match instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorDepth {
+    #[default]
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+// Channel levels of the 6x6x6 cube that starts at colour 16 of the 256
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl ColorDepth {
+    // Terminals announce 24-bit colour through COLORTERM, and 256 colours through
+    // TERM. Windows Terminal sets neither but has full colour, as do modern Windows consoles.
+    pub fn detect() -> Self {
+        let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+        let term = std::env::var("TERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" || (cfg!(windows) && term.is_empty()) {
+            ColorDepth::TrueColor
+        } else if term.contains("256") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        }
+    }
+
+    // The closest colour this depth can show; anything but RGB is already fine
+    pub fn approximate(self, color: Color) -> Color {
+        let Color::Rgb { r, g, b } = color else {
+            return color;
+        };
+        match self {
+            ColorDepth::TrueColor => color,
+            ColorDepth::Ansi256 => {
+                // Nearest of the colour cube and the grey ramp (232-255: 8, 18, ... 238)
+                let level = |channel: u8| {
+                    (0..6).min_by_key(|&i| CUBE_LEVELS[i].abs_diff(channel)).unwrap_or(0)
+                };
+                let (ri, gi, bi) = (level(r), level(g), level(b));
+                let cube = (CUBE_LEVELS[ri], CUBE_LEVELS[gi], CUBE_LEVELS[bi]);
+                let grey_step = ((((r as u32 + g as u32 + b as u32) / 3).saturating_sub(8) + 5) / 10).min(23) as u8;
+                let grey = 8 + 10 * grey_step;
+                if distance((r, g, b), (grey, grey, grey)) < distance((r, g, b), cube) {
+                    Color::AnsiValue(232 + grey_step)
+                } else {
+                    Color::AnsiValue(16 + 36 * ri as u8 + 6 * gi as u8 + bi as u8)
+                }
+            }
+            ColorDepth::Ansi16 => {
+                // Nearest by distance would turn pale neon shades grey, so keep the hue:
+                // a channel counts if it is in the top half of this colour's range
+                let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+                if max - min <= max / 4 {
+                    return match max {
+                        0..=63 => Color::Black,
+                        64..=159 => Color::DarkGrey,
+                        160..=223 => Color::Grey,
+                        _ => Color::White,
+                    };
+                }
+                let mid = (max as u16 + min as u16) / 2;
+                let on = |channel: u8| channel as u16 > mid;
+                let bright = max > 160;
+                match (on(r), on(g), on(b)) {
+                    (true, false, false) => if bright { Color::Red } else { Color::DarkRed },
+                    (false, true, false) => if bright { Color::Green } else { Color::DarkGreen },
+                    (false, false, true) => if bright { Color::Blue } else { Color::DarkBlue },
+                    (true, true, false) => if bright { Color::Yellow } else { Color::DarkYellow },
+                    (true, false, true) => if bright { Color::Magenta } else { Color::DarkMagenta },
+                    (false, true, true) => if bright { Color::Cyan } else { Color::DarkCyan },
+                    _ => if bright { Color::White } else { Color::Grey },
+                }
+            }
+        }
+    }
+}
+
+// Squared distance between two colours, which is all that's needed to compare them
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let channel = |x: u8, y: u8| (x.abs_diff(y) as u32).pow(2);
+    channel(a.0, b.0) + channel(a.1, b.1) + channel(a.2, b.2)
+}
+
+// Running totals while every lost or stuck game is replaced by a fresh deal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DealLoop {
+    pub deal: u32, // Which deal this is, counting from 1
+    pub wins: u32,
+}
+
 pub struct Display {
     pub selected_position: (usize, usize),
     pub hover_pile: Option<(PileType, usize, usize)>,
+    pub tutorial_prompt: Option<&'static str>, // Shown in place of the hint during the tutorial
+    pub ascii: bool,                           // Plain ASCII only, for terminals without Unicode glyphs
+    pub show_move_list: bool,                  // Panel listing every legal move
+    pub show_history: bool,                    // Line giving the undo and redo depths and what each would take back
+    pub draw_odds: bool,                       // Chance the next draw turns up a card that plays, under the stock
+    pub score_ceiling: bool,                   // Highest score still reachable, in the HUD
+    pub score_popups: bool,                    // Points won or lost shown briefly by the pile they came from
+    pub move_list_scroll: usize,               // First move shown in that panel
+    pub show_waste_list: bool,                 // Panel listing the whole waste, top card first
+    pub waste_list_scroll: usize,              // First waste card shown in that panel
+    pub card_back: CardBack,                   // Face-down card artwork
+    pub palette: SuitPalette,                  // Suit colours, including any user overrides
+    pub monochrome: bool,                      // No colours at all; red cards are marked with '*'
+    pub color_depth: ColorDepth,               // Colours the terminal can show, which the neon RGB is fitted to
+    pub layout: Layout,                        // Column spacing and pile positions
+    pub strings: &'static Strings,             // Text in the chosen language
+    pub peek: bool,                            // Draw face-down cards face up; the game itself is untouched
+    pub teacher: bool,                         // Underline the best move on every redraw
+    pub just_drawn: usize,                     // Cards the last draw put on the waste, marked until the next action
+    pub deal_loop: Option<DealLoop>,           // Deal-until-you-win counts, shown above the piles
+    pub debug_layout: bool,                    // Developer overlay of pile positions and card indices
+    pub last_click: Option<(u16, u16)>,        // Screen cell of the latest click, for that overlay
+}
+
+impl Default for Display {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Display {
@@ -18,6 +191,28 @@ impl Display {
         Display {
             selected_position: (0, 0),
             hover_pile: None,
+            tutorial_prompt: None,
+            ascii: false,
+            show_move_list: false,
+            show_history: false,
+            draw_odds: false,
+            score_ceiling: false,
+            score_popups: false,
+            move_list_scroll: 0,
+            show_waste_list: false,
+            waste_list_scroll: 0,
+            card_back: CardBack::default(),
+            palette: SuitPalette::default(),
+            monochrome: false,
+            color_depth: ColorDepth::default(),
+            layout: Layout::default(),
+            strings: &ENGLISH,
+            peek: false,
+            teacher: false,
+            just_drawn: 0,
+            deal_loop: None,
+            debug_layout: false,
+            last_click: None,
         }
     }
 
@@ -48,31 +243,63 @@ impl Display {
         // Move to top-left instead of clearing entire screen
         execute!(stdout(), MoveTo(0, 0))?;
         
-        // Draw title
-        self.draw_title()?;
-        
-        // Draw score and stats
-        self.draw_stats(game)?;
+        // Draw title, unless the HUD has taken its place
+        if !self.layout.hud {
+            self.draw_title()?;
+        }
         
-        // Draw stock and waste
-        self.draw_stock_waste(game)?;
+        self.draw_board(game)?;
         
-        // Draw foundations
-        self.draw_foundations(game)?;
-        
-        // Draw tableau
-        self.draw_tableau(game)?;
+        if self.show_move_list {
+            self.draw_move_list(game)?;
+        }
+        if self.show_waste_list {
+            self.draw_waste_list(game)?;
+        }
         
         // Draw controls hint
-        self.draw_controls()?;
+        self.draw_controls(game)?;
         
         // Draw hint if available
-        execute!(stdout(), MoveTo(0, 23))?;
-        if let Some(hint) = game.get_hint() {
+        execute!(stdout(), MoveTo(0, self.layout.hint_row()))?;
+        if let Some(prompt) = self.tutorial_prompt {
+            execute!(
+                stdout(),
+                self.fg(Color::Rgb { r: 255, g: 255, b: 100 }),
+                Print(format!("{} {}                    ", self.glyph("🎓", ">"), prompt)),
+                ResetColor
+            )?;
+        } else if game.hint.is_none() && game.can_auto_complete() {
+            execute!(
+                stdout(),
+                self.fg(Color::Rgb { r: 100, g: 255, b: 100 }),
+                Print(format!("{} {}          ", self.glyph("💡", "*"), self.strings.auto_complete_ready)),
+                ResetColor
+            )?;
+        } else if let Some(hint) = game.hint.as_ref()
+            .map(|mv| mv.describe_with(self.strings))
+            .or_else(|| game.get_hint_with(self.strings))
+        {
             execute!(
                 stdout(),
-                SetForegroundColor(Color::Rgb { r: 100, g: 255, b: 100 }),
-                Print(format!("💡 Hint: {}                                        ", hint)),
+                self.fg(Color::Rgb { r: 100, g: 255, b: 100 }),
+                Print(format!("{} {}: {}                                        ", self.glyph("💡", "*"), self.strings.hint, hint)),
+                ResetColor
+            )?;
+        } else {
+            execute!(
+                stdout(),
+                Print("                                                                      ")
+            )?;
+        }
+        
+        // Status line for short-lived feedback such as illegal move reasons
+        execute!(stdout(), MoveTo(0, self.layout.status_row()))?;
+        if let Some((message, _)) = &game.status_message {
+            execute!(
+                stdout(),
+                self.fg(Color::Rgb { r: 255, g: 150, b: 80 }),
+                Print(format!("{} {}                                        ", self.glyph("»", ">"), message)),
                 ResetColor
             )?;
         } else {
@@ -86,16 +313,196 @@ impl Display {
         Ok(())
     }
 
+    // Stats, piles and tableau, starting at the layout's left edge
+    fn draw_board(&self, game: &GameState) -> std::io::Result<()> {
+        // Draw score and stats
+        if self.layout.hud {
+            self.draw_hud(game)?;
+        } else {
+            self.draw_stats(game)?;
+        }
+        
+        if let Some(deal_loop) = self.deal_loop {
+            execute!(
+                stdout(),
+                MoveTo(self.layout.x(2), self.layout.y(Layout::PILE_ROW - 1)),
+                self.fg(Color::Rgb { r: 255, g: 150, b: 80 }),
+                Print(format!("Deal until you win: deal {}, {} won", deal_loop.deal, deal_loop.wins)),
+                ResetColor
+            )?;
+        }
+        
+        if self.show_history {
+            self.draw_history(game)?;
+        }
+        
+        // Teacher mode's pick, worked out afresh for every board. It is the move H
+        // would suggest first, so it never badges a pointless whole-column shift.
+        let best = if self.teacher { hint_moves(game).into_iter().next() } else { None };
+        
+        // Draw stock and waste
+        self.draw_stock_waste(game, best.as_ref())?;
+        
+        // Draw foundations
+        self.draw_foundations(game, best.as_ref())?;
+        
+        // Draw tableau
+        self.draw_tableau(game, best.as_ref())?;
+        
+        if self.score_popups {
+            self.draw_score_popup(game)?;
+        }
+        
+        if self.debug_layout {
+            self.draw_debug_overlay(game)?;
+        }
+        
+        Ok(())
+    }
+
+    // Where everything is, for checking mouse hit-testing by eye: tableau row indices
+    // in the margin left of the columns, and pile positions and the latest click's
+    // mapping on rows of their own below the board
+    fn draw_debug_overlay(&self, game: &GameState) -> std::io::Result<()> {
+        let scroll = self.layout.tableau_scroll(game);
+        for row in scroll..scroll + self.layout.tableau_rows() {
+            if let Some(y) = self.layout.row_y(row, scroll) {
+                execute!(stdout(), MoveTo(self.layout.x(0), y), self.fg(DEBUG_FG), Print(format!("{:<2}", row)), ResetColor)?;
+            }
+        }
+        
+        let piles = format!(
+            "piles y{}: stock x{} waste x{} found x{}+{}n | tableau x{}+{}n y{}+ scroll {}",
+            self.layout.y(Layout::PILE_ROW),
+            self.layout.pile_x(PileType::Stock, 0),
+            self.layout.pile_x(PileType::Waste, 0),
+            self.layout.pile_x(PileType::Foundation, 0),
+            Layout::FOUNDATION_WIDTH,
+            self.layout.column_x(0),
+            self.layout.column_width,
+            self.layout.y(Layout::TABLEAU_TOP),
+            scroll
+        );
+        let click = match self.last_click {
+            Some((x, y)) => match convert_mouse_to_game_position(x, y, game, &self.layout) {
+                Some((pile_type, pile, card)) => format!("click ({}, {}) -> {:?} pile {} card {}", x, y, pile_type, pile, card),
+                None => format!("click ({}, {}) -> nothing", x, y),
+            },
+            None => "click somewhere to see where it lands".to_string(),
+        };
+        // Below everything else, so the overlay never covers the board's own text
+        let Some(row) = self.layout.spare_row(2) else {
+            return Ok(());
+        };
+        execute!(
+            stdout(),
+            MoveTo(self.layout.x(0), row),
+            self.fg(DEBUG_FG),
+            Print(format!("{:<80}", piles)),
+            MoveTo(self.layout.x(0), row + 1),
+            Print(format!("{:<80}", click)),
+            ResetColor
+        )?;
+        Ok(())
+    }
+
+    // Both race boards side by side, each with its player's keys and latest message
+    pub fn draw_race(&mut self, race: &Race) -> std::io::Result<()> {
+        execute!(stdout(), MoveTo(0, 0))?;
+        self.draw_title()?;
+        
+        // Each player's two rows of keys, then the quit line, go below everything
+        // the boards show, wherever the terminal's height puts that
+        let keys_row = self.layout.spare_row(3);
+        let saved_left = self.layout.left;
+        for (player, game) in race.boards.iter().enumerate() {
+            self.layout.left = player as u16 * BOARD_WIDTH;
+            self.draw_board(game)?;
+            
+            if let Some(row) = keys_row {
+                let keys = RACE_KEYS[player];
+                execute!(
+                    stdout(),
+                    MoveTo(self.layout.x(2), row),
+                    self.fg(Color::Rgb { r: 150, g: 150, b: 200 }),
+                    Print(format!("[{}] Column | [{}] Draw | [{}] Waste", keys[0], keys[1], keys[2])),
+                    MoveTo(self.layout.x(2), row + 1),
+                    Print(format!("[{}] Foundation | [{}] Undo | [{}] Auto", keys[3], keys[4], keys[5])),
+                    ResetColor
+                )?;
+            }
+            execute!(
+                stdout(),
+                MoveTo(self.layout.x(2), Layout::STATS_ROW - 1),
+                self.fg(Color::Rgb { r: 255, g: 0, b: 255 }),
+                Print(format!("Player {}", player + 1)),
+                MoveTo(self.layout.x(2), self.layout.status_row()),
+                self.fg(Color::Rgb { r: 255, g: 150, b: 80 }),
+                Print(format!(
+                    "{:<width$}",
+                    game.status_message.as_ref().map_or(String::new(), |(message, _)| format!("{} {}", self.glyph("»", ">"), message)),
+                    width = BOARD_WIDTH as usize - 4
+                )),
+                ResetColor
+            )?;
+        }
+        self.layout.left = saved_left;
+        
+        if let Some(row) = keys_row {
+            execute!(
+                stdout(),
+                MoveTo(0, row + 2),
+                self.fg(Color::Rgb { r: 150, g: 150, b: 200 }),
+                Print("[Esc] Quit the race"),
+                ResetColor
+            )?;
+        }
+        stdout().flush()
+    }
+
+    // Shown instead of the race until the terminal is big enough for both boards
+    pub fn draw_race_size_warning(&self, width: u16, height: u16) -> std::io::Result<()> {
+        execute!(
+            stdout(),
+            Clear(ClearType::All),
+            MoveTo(2, 2),
+            self.fg(Color::Rgb { r: 255, g: 150, b: 80 }),
+            Print(format!("Race mode needs a terminal at least {}x{}.", MIN_WIDTH, MIN_HEIGHT)),
+            MoveTo(2, 3),
+            Print(format!("This one is {}x{}; enlarge the window to start.", width, height)),
+            MoveTo(2, 5),
+            Print("Press Esc to quit."),
+            ResetColor
+        )?;
+        stdout().flush()
+    }
+
+    pub fn draw_race_winner(&self, player: usize) -> std::io::Result<()> {
+        execute!(
+            stdout(),
+            Clear(ClearType::All),
+            MoveTo(20, 10),
+            self.fg(Color::Rgb { r: 255, g: 0, b: 255 }),
+            Print(self.rule(30)),
+            MoveTo(20, 11),
+            Print(format!("   {} PLAYER {} WINS! {}", self.glyph("🏆", "*"), player + 1, self.glyph("🏆", "*"))),
+            MoveTo(20, 12),
+            Print(self.rule(30)),
+            ResetColor
+        )?;
+        stdout().flush()
+    }
+
     fn draw_title(&self) -> std::io::Result<()> {
         execute!(
             stdout(),
             MoveTo(20, 0),
-            SetForegroundColor(Color::Rgb { r: 255, g: 0, b: 255 }),
-            Print("═══════════════════════════════════════"),
+            self.fg(Color::Rgb { r: 255, g: 0, b: 255 }),
+            Print(self.rule(39)),
             MoveTo(20, 1),
             Print("      N E O N   S O L I T A I R E     "),
             MoveTo(20, 2),
-            Print("═══════════════════════════════════════"),
+            Print(self.rule(39)),
             ResetColor
         )?;
         Ok(())
@@ -104,66 +511,209 @@ impl Display {
     fn draw_stats(&self, game: &GameState) -> std::io::Result<()> {
         execute!(
             stdout(),
-            MoveTo(2, 4),
-            SetForegroundColor(Color::Rgb { r: 100, g: 200, b: 255 }),
-            Print(format!("Score: {:4} ", game.score)),
-            SetForegroundColor(Color::Rgb { r: 255, g: 200, b: 100 }),
-            Print(format!("Moves: {:4} ", game.move_count)),
-            SetForegroundColor(Color::Rgb { r: 200, g: 100, b: 255 }),
-            Print(format!("Draw: {}     ", if game.draw_count == 1 { "1 card " } else { "3 cards" })),
+            MoveTo(self.layout.x(2), Layout::STATS_ROW),
+            self.fg(Color::Rgb { r: 100, g: 200, b: 255 }),
+            Print(format!("{}: {:4} ", self.strings.score, game.score)),
+            self.fg(Color::Rgb { r: 255, g: 200, b: 100 }),
+            Print(match game.rules.max_moves {
+                Some(max) => format!("{}: {}/{} ", self.strings.moves, game.move_count, max),
+                None => format!("{}: {:4} ", self.strings.moves, game.move_count),
+            }),
+            self.fg(Color::Rgb { r: 200, g: 100, b: 255 }),
+            Print(format!(
+                "{}: {} ",
+                self.strings.draw,
+                self.draw_label(game)
+            )),
+            self.fg(Color::Rgb { r: 150, g: 255, b: 150 }),
+            Print(format!("{}: {:02}:{:02} ", self.strings.time, game.elapsed_secs() / 60, game.elapsed_secs() % 60)),
+            self.fg(Color::Rgb { r: 100, g: 255, b: 100 }),
+            Print(format!("{}: {:<3} ", self.strings.hints, game.hints_used)),
+            ResetColor
+        )?;
+        
+        // Limited-pass games show how many trips through the stock remain
+        if let Some(max_passes) = game.rules.max_passes {
+            execute!(
+                stdout(),
+                self.fg(Color::Rgb { r: 255, g: 150, b: 80 }),
+                Print(format!(
+                    "{}{}: {}/{}   ",
+                    if game.rules.scoring == ScoringMode::Vegas { "Vegas " } else { "" },
+                    self.strings.pass,
+                    game.recycles + 1,
+                    max_passes
+                )),
+                ResetColor
+            )?;
+        }
+        
+        // Padded so a broken combo clears what the last one left behind
+        execute!(
+            stdout(),
+            self.fg(Color::Rgb { r: 255, g: 100, b: 200 }),
+            Print(format!("{:<9}", self.combo_label(game).unwrap_or_default())),
+            ResetColor
+        )?;
+        Ok(())
+    }
+    
+    fn draw_label(&self, game: &GameState) -> &'static str {
+        match game.draw_count {
+            _ if game.rules.draw_all => self.strings.all_cards,
+            1 => self.strings.one_card,
+            _ => self.strings.three_cards,
+        }
+    }
+    
+    // "Combo x3" while a streak is multiplying foundation points
+    fn combo_label(&self, game: &GameState) -> Option<String> {
+        let multiplier = game.rules.combo_multiplier(game.combo);
+        (multiplier > 1).then(|| format!("{} x{}", self.strings.combo, multiplier))
+    }
+
+    // Everything from the stats row, plus the number of legal moves, on the top line
+    fn draw_hud(&self, game: &GameState) -> std::io::Result<()> {
+        let mut parts = vec![
+            format!("{}: {}", self.strings.score, game.score),
+            match game.rules.max_moves {
+                Some(max) => format!("{}: {}/{}", self.strings.moves, game.move_count, max),
+                None => format!("{}: {}", self.strings.moves, game.move_count),
+            },
+            format!("{}: {:02}:{:02}", self.strings.time, game.elapsed_secs() / 60, game.elapsed_secs() % 60),
+            format!("{}: {}", self.strings.draw, self.draw_label(game).trim_end()),
+        ];
+        if let Some(max_passes) = game.rules.max_passes {
+            parts.push(format!("{}: {}/{}", self.strings.pass, game.recycles + 1, max_passes));
+        }
+        parts.push(format!("{}: {}", self.strings.playable, find_valid_moves(game).len()));
+        if let Some(combo) = self.combo_label(game) {
+            parts.push(combo);
+        }
+        if self.score_ceiling {
+            parts.push(format!("{}: {}", self.strings.ceiling, game.score_ceiling()));
+        }
+        
+        execute!(
+            stdout(),
+            MoveTo(self.layout.x(0), 0),
+            self.fg(Color::Rgb { r: 100, g: 200, b: 255 }),
+            Print(format!("{:<78}", format!(" {}", parts.join(" | ")))),
             ResetColor
         )?;
         Ok(())
     }
 
-    fn draw_stock_waste(&self, game: &GameState) -> std::io::Result<()> {
-        execute!(stdout(), MoveTo(2, 6))?;
+    fn draw_stock_waste(&self, game: &GameState, best: Option<&Move>) -> std::io::Result<()> {
+        execute!(stdout(), MoveTo(self.layout.x(2), self.layout.y(Layout::PILE_ROW)))?;
         
         // Draw stock
         execute!(
             stdout(),
-            SetForegroundColor(Color::Rgb { r: 150, g: 150, b: 200 }),
+            self.fg(Color::Rgb { r: 150, g: 150, b: 200 }),
             Print("Stock: "),
             ResetColor
         )?;
         
+        let hinted = game.hint.as_ref().is_some_and(|h| h.from.pile_type == PileType::Stock);
+        if hinted {
+            execute!(stdout(), self.bg(HINT_SOURCE_BG))?;
+        } else if Self::focused(game, PileType::Stock, 0) {
+            execute!(stdout(), self.bg(FOCUS_BG))?;
+        }
+        // An empty stock with cards in the waste means the next draw recycles, so it stands out
+        let recycle_next = game.draw_recycles();
         if game.stock.is_empty() {
             execute!(
                 stdout(),
-                SetForegroundColor(Color::Rgb { r: 100, g: 100, b: 100 }),
-                Print("[♻]  "),
-                ResetColor
+                self.fg(if recycle_next { Color::Rgb { r: 255, g: 150, b: 80 } } else { Color::Rgb { r: 100, g: 100, b: 100 } }),
+                Print(format!("[{}]", self.glyph("♻", "~"))),
+                ResetColor,
+                Print("  ")
             )?;
         } else {
             execute!(
                 stdout(),
-                SetForegroundColor(Color::Rgb { r: 100, g: 100, b: 200 }),
-                Print(format!("[{:2}] ", game.stock.len())),
-                ResetColor
+                self.fg(Color::Rgb { r: 100, g: 100, b: 200 }),
+                Print(format!("[{:2}]", game.stock.len())),
+                ResetColor,
+                Print(" ")
             )?;
         }
         
+        // In draw 3 the stock doesn't always split evenly, so show how it will chunk
+        let (draws, last) = game.stock_draws();
+        let projection = match draws {
+            _ if recycle_next => match game.rules.recycle_penalty() {
+                0 => "Next draw recycles".to_string(),
+                penalty => format!("Next draw recycles (-{})", penalty),
+            },
+            0 if !game.waste.is_empty() => "No passes left".to_string(),
+            _ if game.draw_count == 1 => String::new(),
+            0 => String::new(),
+            1 => format!("1 draw of {}", last),
+            _ => format!("{} draws, last: {}", draws, last),
+        };
+        let odds = match draw_odds(game).filter(|_| self.draw_odds) {
+            Some(odds) if projection.is_empty() => format!("{:.0}% to play", odds * 100.0),
+            Some(odds) => format!(", {:.0}% to play", odds * 100.0),
+            None => String::new(),
+        };
+        execute!(
+            stdout(),
+            MoveTo(self.layout.x(2), self.layout.y(Layout::PILE_ROW) + 1),
+            self.fg(Color::Rgb { r: 100, g: 100, b: 150 }),
+            Print(format!("{:<36}", projection + &odds)),
+            ResetColor,
+            MoveTo(self.layout.x(Layout::STOCK_LEFT + 5), self.layout.y(Layout::PILE_ROW))
+        )?;
+        
         // Draw waste
         execute!(
             stdout(),
-            SetForegroundColor(Color::Rgb { r: 150, g: 150, b: 200 }),
+            self.fg(Color::Rgb { r: 150, g: 150, b: 200 }),
             Print("Waste: "),
             ResetColor
         )?;
-        
-        if game.waste.is_empty() {
+        self.draw_waste(game, game.waste.len(), best)
+    }
+
+    // One frame of the draw animation: the waste fan as if only its first `shown` cards were there
+    pub fn draw_waste_frame(&self, game: &GameState, shown: usize) -> std::io::Result<()> {
+        let (x, y) = (self.layout.x(Layout::WASTE_LEFT), self.layout.y(Layout::PILE_ROW));
+        // Blank the whole fan first, since a frame can show fewer cards than the board did
+        let fan_width = 3 * Layout::WASTE_CARD_WIDTH as usize;
+        execute!(stdout(), MoveTo(x, y), Print(" ".repeat(fan_width)), MoveTo(x, y))?;
+        self.draw_waste(game, shown, None)?;
+        stdout().flush()
+    }
+
+    // The top three of the waste's first `shown` cards, from the cursor
+    fn draw_waste(&self, game: &GameState, shown: usize, best: Option<&Move>) -> std::io::Result<()> {
+        let focused = Self::focused(game, PileType::Waste, 0);
+        if shown == 0 {
+            if focused {
+                execute!(stdout(), self.bg(FOCUS_BG))?;
+            }
             execute!(
                 stdout(),
-                SetForegroundColor(Color::Rgb { r: 100, g: 100, b: 100 }),
-                Print("[ ]          "),
-                ResetColor
+                self.fg(Color::Rgb { r: 100, g: 100, b: 100 }),
+                Print("[ ]"),
+                ResetColor,
+                Print("          ")
             )?;
         } else {
-            let start = if game.waste.len() > 3 { game.waste.len() - 3 } else { 0 };
-            for (i, card) in game.waste[start..].iter().enumerate() {
+            let start = shown.saturating_sub(3);
+            for (i, card) in game.waste[start..shown].iter().enumerate() {
                 let is_selected = game.selected_card == Some((PileType::Waste, 0, start + i));
-                self.draw_card_compact(card, is_selected)?;
-                execute!(stdout(), Print(" "))?;
+                let is_hinted = start + i + 1 == game.waste.len()
+                    && game.hint.as_ref().is_some_and(|h| h.from.pile_type == PileType::Waste);
+                let badged = Self::is_badged(best, PileType::Waste, 0, start + i);
+                let highlight = Self::highlight(is_selected, is_hinted, false)
+                    .or((focused && start + i + 1 == shown).then_some(FOCUS_BG))
+                    .or_else(|| self.just_drawn_highlight(game, start + i));
+                self.draw_badged(card, highlight, badged)?;
+                execute!(stdout(), Print(self.card_gap(card)))?;
             }
             // Clear any remaining space
             execute!(stdout(), Print("          "))?;
@@ -172,95 +722,491 @@ impl Display {
         Ok(())
     }
 
-    fn draw_foundations(&self, game: &GameState) -> std::io::Result<()> {
-        execute!(stdout(), MoveTo(40, 6))?;
+    fn draw_foundations(&self, game: &GameState, best: Option<&Move>) -> std::io::Result<()> {
+        execute!(stdout(), MoveTo(self.layout.x(40), self.layout.y(Layout::PILE_ROW)))?;
         
         execute!(
             stdout(),
-            SetForegroundColor(Color::Rgb { r: 255, g: 200, b: 100 }),
+            self.fg(Color::Rgb { r: 255, g: 200, b: 100 }),
             Print("Foundations: "),
             ResetColor
         )?;
         
-        let suits = ["♥", "♦", "♣", "♠"];
-        let colors = [
-            Color::Rgb { r: 255, g: 50, b: 100 },   // Hearts - Neon Pink
-            Color::Rgb { r: 100, g: 200, b: 255 },  // Diamonds - Neon Blue
-            Color::Rgb { r: 150, g: 255, b: 150 },  // Clubs - Neon Green
-            Color::Rgb { r: 255, g: 255, b: 100 },  // Spades - Neon Yellow
-        ];
-        
         for (i, foundation) in game.foundations.iter().enumerate() {
+            let suit = GameState::foundation_suit(i);
+            let is_target = game.hint.as_ref()
+                .is_some_and(|h| h.to.pile_type == PileType::Foundation && h.to.pile_index == i);
+            let focused = Self::focused(game, PileType::Foundation, i);
             if foundation.is_empty() {
+                if is_target {
+                    execute!(stdout(), self.bg(HINT_TARGET_BG))?;
+                } else if focused {
+                    execute!(stdout(), self.bg(FOCUS_BG))?;
+                }
+                if Self::is_badged(best, PileType::Foundation, i, usize::MAX) {
+                    execute!(stdout(), SetAttribute(Attribute::Underlined))?;
+                }
                 execute!(
                     stdout(),
-                    SetForegroundColor(colors[i]),
-                    Print(format!("[{}] ", suits[i])),
-                    ResetColor
+                    self.fg(self.palette.color(suit)),
+                    Print(format!("[{}]", self.suit_symbol(suit))),
+                    ResetColor,
+                    SetAttribute(Attribute::NoUnderline),
+                    Print(" ")
                 )?;
             } else {
                 let card = foundation.last().unwrap();
-                self.draw_card_compact(card, false)?;
-                execute!(stdout(), Print(" "))?;
+                let badged = Self::is_badged(best, PileType::Foundation, i, foundation.len() - 1);
+                let is_selected = matches!(game.selected_card, Some((PileType::Foundation, s, _)) if s == i);
+                let highlight = Self::highlight(is_selected, false, is_target).or(focused.then_some(FOCUS_BG));
+                self.draw_badged(card, highlight, badged)?;
+                execute!(stdout(), Print(self.card_gap(card)))?;
             }
         }
         
+        // The row below is left for score popups, so clear whatever the last one left there
+        execute!(
+            stdout(),
+            MoveTo(self.layout.x(40), self.layout.y(Layout::PILE_ROW) + 1),
+            Print(" ".repeat(13 + game.foundations.len() * Layout::FOUNDATION_WIDTH as usize))
+        )?;
+        
         Ok(())
     }
+    
+    // The latest score change, just under the pile it came from: below a top-row
+    // pile, or below a column's last card (on the marker row if that is clipped).
+    // Every cell it can land on is repainted by the next full redraw.
+    fn draw_score_popup(&self, game: &GameState) -> std::io::Result<()> {
+        let Some((delta, pile_type, index, _)) = game.score_popup else {
+            return Ok(());
+        };
+        let y = match pile_type {
+            PileType::Tableau => {
+                let rows = self.layout.tableau_rows();
+                let scroll = self.layout.tableau_scroll(game);
+                self.layout.row_y(game.tableau[index].len(), scroll)
+                    .unwrap_or(self.layout.y(Layout::TABLEAU_TOP) + rows as u16)
+            }
+            _ => self.layout.y(Layout::PILE_ROW) + 1,
+        };
+        let (text, color) = if delta > 0 {
+            (format!("+{}", delta), Color::Rgb { r: 100, g: 255, b: 100 })
+        } else {
+            (format!("{}{}", self.glyph("−", "-"), -delta), Color::Rgb { r: 255, g: 100, b: 100 })
+        };
+        execute!(
+            stdout(),
+            MoveTo(self.layout.pile_x(pile_type, index), y),
+            SetAttribute(Attribute::Bold),
+            self.fg(color),
+            Print(text),
+            ResetColor,
+            SetAttribute(Attribute::NormalIntensity)
+        )
+    }
 
-    fn draw_tableau(&self, game: &GameState) -> std::io::Result<()> {
+    fn draw_tableau(&self, game: &GameState, best: Option<&Move>) -> std::io::Result<()> {
         // Column headers
-        execute!(stdout(), MoveTo(2, 9))?;
-        for i in 1..=7 {
+        let width = self.layout.column_width as usize;
+        let scroll = self.layout.tableau_scroll(game);
+        execute!(stdout(), MoveTo(self.layout.x(Layout::TABLEAU_LEFT), self.layout.y(Layout::TABLEAU_HEADER_ROW)))?;
+        for col in 0..game.rules.tableau_columns {
+            // A focused column has its (single-digit) number lit, since the column may be empty
+            execute!(stdout(), Print("  "))?;
+            if Self::focused(game, PileType::Tableau, col) {
+                execute!(stdout(), self.bg(FOCUS_BG))?;
+            }
             execute!(
                 stdout(),
-                SetForegroundColor(Color::Rgb { r: 200, g: 200, b: 255 }),
-                Print(format!("  {}   ", i)),
-                ResetColor
+                self.fg(Color::Rgb { r: 200, g: 200, b: 255 }),
+                Print(col + 1),
+                ResetColor,
+                Print(" ".repeat(width - 3))
             )?;
         }
         
         // Find max column height
         let max_height = game.tableau.iter().map(|col| col.len()).max().unwrap_or(0);
+        let clear_width = self.layout.tableau_width(game.rules.tableau_columns) + 8;
         
-        // Draw cards - add padding to clear old cards
-        for row in 0..(max_height + 5) {
-            execute!(stdout(), MoveTo(2, 10 + row as u16))?;
+        // Tall columns are clipped to the rows that fit, with a marker on each clipped side
+        let more_marker = |shown: bool, glyph: &'static str| if shown { format!("{} more", glyph) } else { "      ".to_string() };
+        execute!(
+            stdout(),
+            self.fg(Color::Rgb { r: 200, g: 200, b: 255 }),
+            Print(more_marker(scroll > 0, self.glyph("▲", "^"))),
+            ResetColor
+        )?;
+        
+        // Draw every visible row, blank ones included, so nothing is left over from before
+        let rows = self.layout.tableau_rows();
+        let top = self.layout.y(Layout::TABLEAU_TOP);
+        for row in scroll..scroll + rows {
+            execute!(stdout(), MoveTo(self.layout.x(Layout::TABLEAU_LEFT), top + (row - scroll) as u16))?;
             
-            if row < max_height {
-                for col in 0..7 {
+            if row < max_height.max(1) {
+                for col in 0..game.rules.tableau_columns {
+                    let (is_source, is_target) = match &game.hint {
+                        Some(h) => (
+                            h.from.pile_type == PileType::Tableau && h.from.pile_index == col && row >= h.from.card_index,
+                            h.to.pile_type == PileType::Tableau && h.to.pile_index == col
+                                && row + 1 == game.tableau[col].len().max(1),
+                        ),
+                        None => (false, false),
+                    };
                     if row < game.tableau[col].len() {
                         let card = &game.tableau[col][row];
-                        let is_selected = game.selected_card == Some((PileType::Tableau, col, row));
-                        self.draw_card_compact(card, is_selected)?;
+                        // A selection moves everything from its anchor down, so light the whole run
+                        let is_selected = matches!(
+                            game.selected_card,
+                            Some((PileType::Tableau, c, from_row)) if c == col && row >= from_row
+                        );
+                        let badged = Self::is_badged(best, PileType::Tableau, col, row);
+                        self.draw_badged(card, Self::highlight(is_selected, is_source, is_target), badged)?;
+                        // Pad to the column width so face-down and face-up cards stay aligned
+                        let card_width = if self.shows_face(card) { 5 } else { 4 };
+                        execute!(stdout(), Print(self.card_gap(card)), Print(" ".repeat(width - 1 - card_width)))?;
+                    } else if is_target {
+                        // Empty column the hint points at
+                        execute!(
+                            stdout(),
+                            self.bg(HINT_TARGET_BG),
+                            Print("[  ]"),
+                            ResetColor,
+                            Print(" ".repeat(width - 4))
+                        )?;
+                    } else if row == 0 && Self::is_badged(best, PileType::Tableau, col, usize::MAX) {
+                        // Empty column the best move fills
+                        execute!(
+                            stdout(),
+                            SetAttribute(Attribute::Underlined),
+                            Print("[  ]"),
+                            SetAttribute(Attribute::NoUnderline),
+                            Print(" ".repeat(width - 4))
+                        )?;
                     } else {
-                        execute!(stdout(), Print("      "))?;
+                        execute!(stdout(), Print(" ".repeat(width)))?;
                     }
                 }
             } else {
                 // Clear remaining rows
-                execute!(stdout(), Print("                                                  "))?;
+                execute!(stdout(), Print(" ".repeat(clear_width)))?;
+            }
+        }
+        
+        let clipped_below = max_height > scroll + rows;
+        execute!(
+            stdout(),
+            MoveTo(self.layout.x(Layout::TABLEAU_LEFT), top + rows as u16),
+            self.fg(Color::Rgb { r: 200, g: 200, b: 255 }),
+            Print(format!("{:<w$}", more_marker(clipped_below, self.glyph("▼", "v")), w = clear_width)),
+            ResetColor
+        )?;
+        
+        Ok(())
+    }
+
+    // Repaints just the selected cards in the normal or bright shade, for the pulse effect
+    pub fn draw_selection_pulse(&self, game: &GameState, bright: bool) -> std::io::Result<()> {
+        let background = if bright { SELECTED_PULSE_BG } else { SELECTED_BG };
+        match game.selected_card {
+            Some((PileType::Waste, _, index)) => {
+                let first_shown = game.waste.len().saturating_sub(3);
+                if let Some(card) = game.waste.get(index).filter(|_| index >= first_shown) {
+                    let x = self.layout.x(Layout::WASTE_LEFT + (index - first_shown) as u16 * Layout::WASTE_CARD_WIDTH);
+                    execute!(stdout(), MoveTo(x, self.layout.y(Layout::PILE_ROW)))?;
+                    self.draw_card_compact(card, Some(background))?;
+                }
+            }
+            Some((PileType::Foundation, f, _)) => {
+                if let Some(card) = game.foundations[f].last() {
+                    execute!(stdout(), MoveTo(self.layout.pile_x(PileType::Foundation, f), self.layout.y(Layout::PILE_ROW)))?;
+                    self.draw_card_compact(card, Some(background))?;
+                }
+            }
+            Some((PileType::Tableau, col, from_row)) => {
+                let scroll = self.layout.tableau_scroll(game);
+                for (row, card) in game.tableau[col].iter().enumerate().skip(from_row) {
+                    if let Some(y) = self.layout.row_y(row, scroll) {
+                        execute!(stdout(), MoveTo(self.layout.column_x(col), y))?;
+                        self.draw_card_compact(card, Some(background))?;
+                    }
+                }
+            }
+            _ => {}
+        }
+        stdout().flush()
+    }
+    
+    // Every legal move in short notation, e.g. "T3→T5: 9♠", to the right of the tableau
+    fn draw_move_list(&self, game: &GameState) -> std::io::Result<()> {
+        let moves = find_valid_moves(game);
+        let left = (self.layout.tableau_width(game.rules.tableau_columns) + 12) as u16;
+        let first = self.move_list_scroll.min(moves.len().saturating_sub(MOVE_LIST_ROWS));
+        let more_above = first > 0;
+        let more_below = first + MOVE_LIST_ROWS < moves.len();
+        
+        execute!(
+            stdout(),
+            MoveTo(left, MOVE_LIST_TOP),
+            self.fg(Color::Rgb { r: 200, g: 200, b: 255 }),
+            Print(format!(
+                "{:<width$}",
+                format!(
+                    "Legal moves ({}) {}{}",
+                    moves.len(),
+                    if more_above { self.glyph("▲", "^") } else { " " },
+                    if more_below { self.glyph("▼", "v") } else { " " }
+                ),
+                width = MOVE_LIST_WIDTH
+            )),
+            ResetColor
+        )?;
+        
+        for row in 0..MOVE_LIST_ROWS {
+            let line = match moves.get(first + row) {
+                Some(mv) => self.move_notation(mv),
+                None if row == 0 && moves.is_empty() => "(none, draw a card)".to_string(),
+                None => String::new(),
+            };
+            execute!(
+                stdout(),
+                MoveTo(left, MOVE_LIST_TOP + 1 + row as u16),
+                self.fg(Color::Rgb { r: 100, g: 255, b: 100 }),
+                Print(format!("{:<width$}", line, width = MOVE_LIST_WIDTH)),
+                ResetColor
+            )?;
+        }
+        
+        Ok(())
+    }
+    
+    // Keeps the panel from scrolling past either end of the list
+    pub fn scroll_move_list(&mut self, game: &GameState, down: bool) {
+        let last = find_valid_moves(game).len().saturating_sub(MOVE_LIST_ROWS);
+        self.move_list_scroll = if down {
+            (self.move_list_scroll + 1).min(last)
+        } else {
+            self.move_list_scroll.min(last).saturating_sub(1)
+        };
+    }
+    
+    // Every card drawn this pass, newest first, beside the move list if that is open too.
+    // Only the first is playable; the rest are just for looking back over.
+    fn draw_waste_list(&self, game: &GameState) -> std::io::Result<()> {
+        let mut left = (self.layout.tableau_width(game.rules.tableau_columns) + 12) as u16;
+        if self.show_move_list {
+            left += MOVE_LIST_WIDTH as u16 + 2;
+        }
+        let count = game.waste.len();
+        let first = self.waste_list_scroll.min(count.saturating_sub(MOVE_LIST_ROWS));
+        execute!(
+            stdout(),
+            MoveTo(left, MOVE_LIST_TOP),
+            self.fg(Color::Rgb { r: 200, g: 200, b: 255 }),
+            Print(format!(
+                "{:<width$}",
+                format!(
+                    "Waste ({}) {}{}",
+                    count,
+                    if first > 0 { self.glyph("▲", "^") } else { " " },
+                    if first + MOVE_LIST_ROWS < count { self.glyph("▼", "v") } else { " " }
+                ),
+                width = WASTE_LIST_WIDTH
+            )),
+            ResetColor
+        )?;
+        
+        for row in 0..MOVE_LIST_ROWS {
+            let depth = first + row;
+            let y = MOVE_LIST_TOP + 1 + row as u16;
+            execute!(stdout(), MoveTo(left, y), Print(" ".repeat(WASTE_LIST_WIDTH)), MoveTo(left, y))?;
+            let Some(card) = count.checked_sub(depth + 1).map(|i| &game.waste[i]) else {
+                if depth == 0 {
+                    execute!(stdout(), self.fg(Color::Rgb { r: 100, g: 100, b: 100 }), Print("(empty)"), ResetColor)?;
+                }
+                continue;
+            };
+            execute!(stdout(), Print(if depth == 0 { self.glyph("▶ ", "> ") } else { "  " }))?;
+            self.draw_card_compact(card, None)?;
+            if depth == 0 {
+                execute!(stdout(), self.fg(Color::Rgb { r: 100, g: 255, b: 100 }), Print(" play"), ResetColor)?;
             }
         }
         
         Ok(())
     }
+    
+    pub fn scroll_waste_list(&mut self, game: &GameState, down: bool) {
+        let last = game.waste.len().saturating_sub(MOVE_LIST_ROWS);
+        self.waste_list_scroll = if down {
+            (self.waste_list_scroll + 1).min(last)
+        } else {
+            self.waste_list_scroll.min(last).saturating_sub(1)
+        };
+    }
+    
+    // How far undo and redo can go, and the move each would take back or replay,
+    // on the row above the foundations
+    fn draw_history(&self, game: &GameState) -> std::io::Result<()> {
+        let describe = |kind: UndoKind, before: &GameState, after: &GameState| match move_between(before, after) {
+            _ if kind == UndoKind::AutoComplete => "auto-complete".to_string(),
+            Some(mv) if mv.from.pile_type == PileType::Stock => "draw".to_string(),
+            Some(mv) if mv.to.pile_type == PileType::Stock => "recycle".to_string(),
+            Some(mv) => self.move_notation(&mv),
+            None => "flip".to_string(), // Only turning a card over moves nothing between piles
+        };
+        let side = |label: &str, depth: usize, top: Option<String>| match top {
+            Some(top) => format!("{} {} {}", label, depth, top),
+            None => format!("{} 0", label),
+        };
+        let undo = side("Undo", game.undo_stack.len(), game.undo_stack.last().map(|entry| describe(entry.kind, &entry.state, game)));
+        let redo = side("Redo", game.redo_stack.len(), game.redo_stack.last().map(|entry| describe(entry.kind, game, &entry.state)));
+        execute!(
+            stdout(),
+            MoveTo(self.layout.x(40), self.layout.y(Layout::PILE_ROW - 1)),
+            self.fg(Color::Rgb { r: 180, g: 180, b: 220 }),
+            Print(format!("{:<40}", format!("{} | {}", undo, redo))),
+            ResetColor
+        )?;
+        Ok(())
+    }
+
+    fn move_notation(&self, mv: &Move) -> String {
+        let pile = |location: &MoveLocation, card: Option<&Card>| match location.pile_type {
+            PileType::Tableau => format!("T{}", location.pile_index + 1),
+            PileType::Foundation => format!("F{}", card.map_or("", |c| self.suit_symbol(c.suit))),
+            PileType::Waste => "W".to_string(),
+            PileType::Stock => "S".to_string(),
+        };
+        let card = mv.cards.first();
+        format!(
+            "{}{}{}: {}",
+            pile(&mv.from, card),
+            self.glyph("→", "->"),
+            pile(&mv.to, card),
+            card.map_or(String::new(), |c| format!("{}{}", c.rank.symbol(), self.suit_symbol(c.suit)))
+        )
+    }
+
+    pub fn fg(&self, color: Color) -> Paint {
+        Paint { color: self.color_depth.approximate(color), background: false, monochrome: self.monochrome }
+    }
 
-    fn draw_card_compact(&self, card: &Card, selected: bool) -> std::io::Result<()> {
+    pub fn bg(&self, color: Color) -> Paint {
+        Paint { color: self.color_depth.approximate(color), background: true, monochrome: self.monochrome }
+    }
+
+    // The single column after a face-up card; in monochrome it marks red suits
+    fn card_gap(&self, card: &Card) -> &'static str {
+        if self.monochrome && self.shows_face(card) && card.is_red() { "*" } else { " " }
+    }
+
+    // Whether a card is drawn face up, which peeking does for every card
+    fn shows_face(&self, card: &Card) -> bool {
+        card.face_up || self.peek
+    }
+
+    // Picks the Unicode glyph, or its stand-in in ASCII mode
+    fn glyph(&self, unicode: &'static str, ascii: &'static str) -> &'static str {
+        if self.ascii { ascii } else { unicode }
+    }
+
+    // Styles drawn with block characters fall back to the classic back in ASCII mode
+    fn card_back(&self) -> CardBack {
+        if self.ascii && !self.card_back.pattern().is_ascii() {
+            CardBack::Classic
+        } else {
+            self.card_back
+        }
+    }
+
+    fn suit_symbol(&self, suit: Suit) -> &'static str {
+        if self.ascii { suit.letter() } else { suit.symbol() }
+    }
+
+    // Horizontal divider of the given width
+    fn rule(&self, width: usize) -> String {
+        self.glyph("═", "=").repeat(width)
+    }
+
+    // Whether the keyboard cursor is on this pile
+    fn focused(game: &GameState, pile_type: PileType, index: usize) -> bool {
+        game.cursor == Some((pile_type, index))
+    }
+
+    // Selection wins over hint colours when a card is both
+    fn highlight(selected: bool, hint_source: bool, hint_target: bool) -> Option<Color> {
+        if selected {
+            Some(SELECTED_BG)
+        } else if hint_source {
+            Some(HINT_SOURCE_BG)
+        } else if hint_target {
+            Some(HINT_TARGET_BG)
+        } else {
+            None
+        }
+    }
+
+    // Whether the teacher's best move starts at this card or lands on it. An empty
+    // pile is asked about with usize::MAX, which matches a move landing on it.
+    fn is_badged(best: Option<&Move>, pile_type: PileType, pile_index: usize, card_index: usize) -> bool {
+        best.is_some_and(|mv| {
+            (mv.from.pile_type == pile_type && mv.from.pile_index == pile_index && mv.from.card_index == card_index)
+                || (mv.to.pile_type == pile_type && mv.to.pile_index == pile_index && mv.to.card_index == card_index.wrapping_add(1))
+        })
+    }
+
+    // The newest card on the waste stands out after a draw, and the rest of a
+    // draw-3 group more faintly. Reverse video would read as a selection, so
+    // monochrome mode goes without.
+    fn just_drawn_highlight(&self, game: &GameState, index: usize) -> Option<Color> {
+        if self.monochrome || index < game.waste.len().saturating_sub(self.just_drawn) {
+            None
+        } else if index + 1 == game.waste.len() {
+            Some(JUST_DRAWN_TOP_BG)
+        } else {
+            Some(JUST_DRAWN_BG)
+        }
+    }
+
+    fn draw_badged(&self, card: &Card, highlight: Option<Color>, badged: bool) -> std::io::Result<()> {
+        if !badged {
+            return self.draw_card_compact(card, highlight);
+        }
+        execute!(stdout(), SetAttribute(Attribute::Underlined))?;
+        self.draw_card_compact(card, highlight)?;
+        execute!(stdout(), SetAttribute(Attribute::NoUnderline))
+    }
+
+    fn draw_card_compact(&self, card: &Card, highlight: Option<Color>) -> std::io::Result<()> {
+        // A peeked card is drawn in a muted shade so it can't be mistaken for one really face up
+        if !card.face_up && self.peek {
+            if let Some(background) = highlight {
+                execute!(stdout(), self.bg(background))?;
+            }
+            return execute!(
+                stdout(),
+                self.fg(Color::Rgb { r: 120, g: 120, b: 160 }),
+                Print(format!("[{:<2}{}]", card.rank.symbol(), self.suit_symbol(card.suit))),
+                ResetColor
+            );
+        }
         if !card.face_up {
-            if selected {
+            if let Some(background) = highlight {
                 execute!(
                     stdout(),
-                    SetBackgroundColor(Color::Rgb { r: 100, g: 0, b: 100 }),
-                    SetForegroundColor(Color::Rgb { r: 150, g: 150, b: 200 }),
-                    Print("[??]"),
+                    self.bg(background),
+                    self.fg(Color::Rgb { r: 150, g: 150, b: 200 }),
+                    Print(self.card_back().compact()),
                     ResetColor
                 )?;
             } else {
                 execute!(
                     stdout(),
-                    SetForegroundColor(Color::Rgb { r: 100, g: 100, b: 150 }),
-                    Print("[??]"),
+                    self.fg(Color::Rgb { r: 100, g: 100, b: 150 }),
+                    Print(self.card_back().compact()),
                     ResetColor
                 )?;
             }
@@ -281,25 +1227,20 @@ impl Display {
                 crate::card::Rank::King => "K ",
             };
             
-            let suit_char = match card.suit {
-                crate::card::Suit::Hearts => "♥",
-                crate::card::Suit::Diamonds => "♦",
-                crate::card::Suit::Clubs => "♣",
-                crate::card::Suit::Spades => "♠",
-            };
+            let suit_char = self.suit_symbol(card.suit);
             
-            if selected {
+            if let Some(background) = highlight {
                 execute!(
                     stdout(),
-                    SetBackgroundColor(Color::Rgb { r: 100, g: 0, b: 100 }),
-                    SetForegroundColor(card.get_color()),
+                    self.bg(background),
+                    self.fg(self.palette.color(card.suit)),
                     Print(format!("[{}{}]", rank_str, suit_char)),
                     ResetColor
                 )?;
             } else {
                 execute!(
                     stdout(),
-                    SetForegroundColor(card.get_color()),
+                    self.fg(self.palette.color(card.suit)),
                     Print(format!("[{}{}]", rank_str, suit_char)),
                     ResetColor
                 )?;
@@ -309,37 +1250,166 @@ impl Display {
         Ok(())
     }
 
-    fn draw_controls(&self) -> std::io::Result<()> {
+    // Left off terminals too short for them, rather than drawn past the bottom
+    fn draw_controls(&self, game: &GameState) -> std::io::Result<()> {
+        let Some(top) = self.layout.controls_row() else {
+            return Ok(());
+        };
         execute!(
             stdout(),
-            MoveTo(0, 25),
-            SetForegroundColor(Color::Rgb { r: 150, g: 150, b: 200 }),
-            Print("═══════════════════════════════════════════════════════════════"),
-            MoveTo(0, 26),
-            Print("[1-7] Select Column | [W] Waste | [S] Stock | [F] Foundation  "),
-            MoveTo(0, 27),
-            Print("[Space] Draw | [Z] Undo | [H] Hint | [A] Auto | [Q] Quit     "),
-            MoveTo(0, 28),
-            Print("═══════════════════════════════════════════════════════════════"),
+            MoveTo(0, top),
+            self.fg(Color::Rgb { r: 150, g: 150, b: 200 }),
+            Print(format!("[1-{}] {:<72}", game.rules.tableau_columns, self.strings.controls)),
+            ResetColor
+        )?;
+        
+        Ok(())
+    }
+
+    pub fn draw_pause_screen(&self) -> std::io::Result<()> {
+        // The board is hidden while paused so it can't be studied off the clock
+        execute!(
+            stdout(),
+            Clear(ClearType::All),
+            MoveTo(20, 10),
+            self.fg(Color::Rgb { r: 100, g: 200, b: 255 }),
+            Print(self.rule(36)),
+            MoveTo(20, 11),
+            Print(format!("         {}  P A U S E D            ", self.glyph("⏸", "||"))),
+            MoveTo(20, 12),
+            Print(format!("    Paused {} press P to resume      ", self.glyph("—", "-"))),
+            MoveTo(20, 13),
+            Print(self.rule(36)),
             ResetColor
         )?;
         
         Ok(())
     }
 
+    // Every key and what it does, over a cleared screen in two columns,
+    // with a note on what the mouse setting trades away either way
+    pub fn draw_key_reference(&self, mouse: bool) -> std::io::Result<()> {
+        let half = KEY_REFERENCE.len().div_ceil(2);
+        execute!(
+            stdout(),
+            Clear(ClearType::All),
+            MoveTo(2, 1),
+            self.fg(Color::Rgb { r: 100, g: 200, b: 255 }),
+            Print("KEYS"),
+            MoveTo(2, 2),
+            Print(self.rule(80)),
+            self.fg(Color::Rgb { r: 200, g: 200, b: 255 })
+        )?;
+        for (i, (key, action)) in KEY_REFERENCE.iter().enumerate() {
+            let (x, y) = if i < half { (2, 3 + i) } else { (44, 3 + i - half) };
+            execute!(stdout(), MoveTo(x, y as u16), Print(format!("{:<12}{}", key, action)))?;
+        }
+        let mouse_note = if mouse {
+            "Mouse on: clicks play cards; hold Shift to select text, or use --no-mouse"
+        } else {
+            "Mouse off: keyboard only, and the terminal's text selection works"
+        };
+        execute!(
+            stdout(),
+            MoveTo(2, 4 + half as u16),
+            self.fg(Color::Rgb { r: 150, g: 150, b: 200 }),
+            Print(mouse_note),
+            MoveTo(2, 5 + half as u16),
+            Print("Press any key to return"),
+            ResetColor
+        )?;
+        
+        Ok(())
+    }
+
+    // Lifetime statistics in a box over the middle of the board, which stays
+    // underneath to be redrawn once the box is dismissed
+    pub fn draw_stats_overlay(&self, stats: &Stats) -> std::io::Result<()> {
+        let best_time = stats.best_time_secs.map_or("-".to_string(), |secs| format!("{:02}:{:02}", secs / 60, secs % 60));
+        let lines = [
+            "        LIFETIME STATISTICS".to_string(),
+            format!("  Games played:   {}", stats.games_played),
+            format!("  Win rate:       {:.1}% ({} won)", stats.win_rate(), stats.games_won),
+            format!("  Best time:      {}", best_time),
+            format!("  Best score:     {}", stats.best_score),
+            format!("  Current streak: {}", stats.current_streak),
+            format!("  Longest streak: {}", stats.longest_streak),
+            "  Press any key to return".to_string(),
+        ];
+        execute!(stdout(), MoveTo(20, 8), self.fg(Color::Rgb { r: 100, g: 200, b: 255 }), Print(self.rule(38)))?;
+        for (i, line) in lines.iter().enumerate() {
+            execute!(stdout(), MoveTo(20, 9 + i as u16), Print(format!("{:<38}", line)))?;
+        }
+        execute!(
+            stdout(),
+            MoveTo(20, 9 + lines.len() as u16),
+            Print(self.rule(38)),
+            ResetColor
+        )?;
+        stdout().flush()
+    }
+
+    pub fn draw_idle_screen(&self) -> std::io::Result<()> {
+        execute!(
+            stdout(),
+            Clear(ClearType::All),
+            MoveTo(20, 10),
+            self.fg(Color::Rgb { r: 90, g: 90, b: 110 }),
+            Print(self.rule(36)),
+            MoveTo(20, 11),
+            Print(format!("           {}  I D L E             ", self.glyph("💤", "zz"))),
+            MoveTo(20, 12),
+            Print(format!("  Timer stopped {} press any key     ", self.glyph("—", "-"))),
+            MoveTo(20, 13),
+            Print(self.rule(36)),
+            ResetColor
+        )?;
+        
+        Ok(())
+    }
+
+    pub fn draw_out_of_moves(&self, max_moves: u32) -> std::io::Result<()> {
+        execute!(
+            stdout(),
+            Clear(ClearType::All),
+            MoveTo(20, 10),
+            self.fg(Color::Rgb { r: 255, g: 150, b: 80 }),
+            Print(self.rule(36)),
+            MoveTo(20, 11),
+            Print("      O U T   O F   M O V E S      "),
+            MoveTo(20, 12),
+            Print(format!("   All {} moves used, no win      ", max_moves)),
+            MoveTo(20, 13),
+            Print(self.rule(36)),
+            ResetColor
+        )?;
+        
+        Ok(())
+    }
+
+    pub fn clear_screen(&self) -> std::io::Result<()> {
+        execute!(stdout(), Clear(ClearType::All))?;
+        Ok(())
+    }
+
     pub fn draw_win_animation(&self) -> std::io::Result<()> {
         execute!(
             stdout(),
             Clear(ClearType::All),
             MoveTo(20, 10),
-            SetForegroundColor(Color::Rgb { r: 255, g: 50, b: 255 }),
-            Print("════════════════════════════════════"),
+            self.fg(Color::Rgb { r: 255, g: 50, b: 255 }),
+            Print(self.rule(36)),
             MoveTo(20, 11),
-            Print("    🎉  Y O U   W I N !  🎉        "),
+            Print(format!("    {0}  Y O U   W I N !  {0}        ", self.glyph("🎉", "**"))),
             MoveTo(20, 12),
             Print("    N E O N   V I C T O R Y        "),
             MoveTo(20, 13),
-            Print("════════════════════════════════════"),
+            Print(self.rule(36)),
+            MoveTo(20, 15),
+            self.fg(Color::Rgb { r: 150, g: 150, b: 150 }),
+            Print("[Z] Undo to look back   [N] New game"),
+            MoveTo(20, 16),
+            Print("Any other key to finish"),
             ResetColor
         )?;
         
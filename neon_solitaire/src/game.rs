@@ -1,18 +1,119 @@
-use crate::card::{Card, Rank, create_standard_deck};
+use crate::card::{Card, Rank, Suit};
+use crate::i18n::{Strings, ENGLISH};
+use crate::moves::{is_stuck, Move, MoveLocation};
+use crate::rules::{RulesConfig, ScoringMode};
+use crate::replay::Step;
+use crate::solver::{solve, SolveResult, TRIVIAL_WIN_BUDGET};
+use crate::timer::GameTimer;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::{thread_rng, Rng, SeedableRng};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+// Most memory all the undo snapshots together may take, so a many-deck board
+// keeps fewer of them, or none, rather than copying without end
+const UNDO_MEMORY_BUDGET: usize = 32 << 20;
 
 #[derive(Debug, Clone)]
 pub struct GameState {
-    pub tableau: Vec<Vec<Card>>,  // 7 columns of cards
+    pub tableau: Vec<Vec<Card>>,  // One pile per tableau column (7 in standard Klondike)
     pub stock: Vec<Card>,          // Draw pile (face down)
     pub waste: Vec<Card>,          // Cards drawn from stock (face up)
-    pub foundations: Vec<Vec<Card>>, // 4 piles for each suit (Ace to King)
+    pub foundations: Vec<Vec<Card>>, // One pile per suit per deck (Ace to King)
     pub selected_card: Option<(PileType, usize, usize)>, // What's currently selected
     pub move_count: u32,
     pub score: i32,
-    pub undo_stack: Vec<GameState>,
+    pub undo_stack: Vec<UndoEntry>,
+    pub redo_stack: Vec<UndoEntry>, // Positions undone since the last new action, latest last
     pub draw_count: usize,        // How many cards to draw (1 or 3)
+    pub seed: u64,                // Seed used to shuffle this deal
+    pub history: Vec<Step>,       // Every draw and move that led here, for replays; undo takes steps back off
+    pub opening: Option<String>,  // Position string from before the first step in history
+    pub par: Option<u32>,         // Moves the solver needed to win this deal, if it found a way
+    pub timer: GameTimer,         // Play time, excluding pauses
+    pub move_rejected: bool,      // Set when the last attempted move was illegal
+    pub status_message: Option<(String, Instant)>, // Short-lived feedback and when it was shown
+    pub score_popup: Option<(i32, PileType, usize, Instant)>, // Last points won or lost, the pile they came from, and when
+    pub hint: Option<Move>,       // Suggestion currently highlighted on the board
+    pub hint_cycle: usize,        // Which candidate the next hint press shows
+    pub cursor: Option<(PileType, usize)>, // Pile the arrow keys have focused, kept through undo
+    pub rules: RulesConfig,       // Variant settings this deal was made with
+    pub recycles: u32,            // Times the waste has been turned back into the stock
+    pub celebrated_foundations: Vec<bool>, // Completions already announced, kept through undo
+    pub hints_used: u32,          // Hints asked for this game, kept through undo
+    pub peeked: bool,             // Face-down cards were revealed at some point, kept through undo
+    pub combo: u32,               // Foundation moves in a row, broken by a tableau move or a draw
+    pub event_hook: EventHook,    // Optional listener for notable events
+    pub strings: &'static Strings, // Language for the messages the game itself shows
+}
+
+// Notable things that happen during play, so front-ends and loggers
+// can react without comparing the state before and after every action
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameEvent {
+    MoveMade,                   // Any move or draw, including ones made automatically
+    FoundationCompleted(usize), // The foundation at this index reached its top rank
+    GameWon,
+    StockRecycled,              // The waste was turned back into the stock
+}
+
+// A callback for GameEvents, none by default. Clones of a game (undo
+// snapshots included) share it, so it keeps working after an undo.
+#[derive(Clone, Default)]
+pub struct EventHook(Option<Rc<EventCallback>>);
+
+type EventCallback = dyn Fn(&GameEvent);
+
+impl fmt::Debug for EventHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.0.is_some() { "EventHook(set)" } else { "EventHook(none)" })
+    }
+}
+
+// What the player did from an undo snapshot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UndoKind {
+    Draw,         // Drew from the stock or recycled the waste
+    Move,         // Moved cards between piles
+    AutoComplete, // A whole auto-complete run, undone and redone as one
+}
+
+#[derive(Debug, Clone)]
+pub struct UndoEntry {
+    pub state: GameState,
+    pub kind: UndoKind,
+}
+
+// Where every card is and which way up, for spotting a position seen before.
+// Score, moves, selection and undo history don't count.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PositionKey(Vec<u8>);
+
+// Why a card can't go where it was sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveRejection {
+    WrongColor,            // Same colour as the card it would cover
+    WrongRank,             // Not exactly one rank below the card it would cover
+    EmptyNeedsKing,        // Empty columns only take the top rank
+    FoundationWrongSuit,   // The foundation is building a different suit
+    FoundationNeedsAce,    // Foundations start with an Ace
+    FoundationOutOfOrder,  // Not the next rank up, including on a completed foundation
+    TargetFaceDown,        // The column's top card hasn't been turned over yet
+}
+
+// How a game finished, for the summary once it is over
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameOverReason {
+    Won,
+    MoveLimit, // Challenge mode's move budget ran out
+    Stuck,     // No move left that could help
+    Quit,      // Left with moves still to play
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -23,46 +124,348 @@ pub enum PileType {
     Foundation,
 }
 
+impl Default for GameState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl GameState {
     pub fn new() -> Self {
-        let mut deck = create_standard_deck();
-        deck.shuffle(&mut thread_rng());
+        Self::new_with_seed(thread_rng().gen())
+    }
+
+    pub fn new_with_seed(seed: u64) -> Self {
+        Self::new_with_rules(RulesConfig::default(), seed)
+    }
+
+    pub fn new_with_rules(rules: RulesConfig, seed: u64) -> Self {
+        Self::new_with_rng(rules, seed, &mut StdRng::seed_from_u64(seed))
+    }
+
+    // Shuffles with any random source, so tests can supply a fixed sequence.
+    // The seed is only recorded, e.g. in the results file; it isn't used to shuffle.
+    pub fn new_with_rng(rules: RulesConfig, seed: u64, rng: &mut impl Rng) -> Self {
+        let rules = rules.validated();
+        let mut deck = rules.create_deck();
+        // SliceRandom::shuffle is a Fisher-Yates shuffle, so given an unbiased
+        // generator every ordering of the deck is equally likely
+        deck.shuffle(rng);
+        let game = Self::deal_with_rules(deck, seed, rules);
+        debug_assert!(game.deck_is_valid(), "shuffle lost or duplicated a card");
+        game
+    }
+
+    // Practice for the end of a game: every foundation starts built up to `height`
+    // (capped a rank short of complete), and the rest of the deck is shuffled and
+    // dealt in the usual pattern for as long as it lasts, with the remainder as stock
+    pub fn new_endgame(rules: RulesConfig, seed: u64, height: Rank) -> Self {
+        let rules = rules.validated();
+        let height = (height as u8).min(rules.highest_rank() as u8 - 1);
+        let mut game = Self::empty(rules, seed);
+        let mut deck = rules.create_deck();
         
-        let mut game = GameState {
-            tableau: vec![Vec::new(); 7],
-            stock: Vec::new(),
-            waste: Vec::new(),
-            foundations: vec![Vec::new(); 4],
-            selected_card: None,
-            move_count: 0,
-            score: 0,
-            undo_stack: Vec::new(),
-            draw_count: 3, // Default to draw 3
-        };
+        for (f, foundation) in game.foundations.iter_mut().enumerate() {
+            let suit = Self::foundation_suit(f);
+            for rank in 1..=height {
+                let index = deck.iter()
+                    .position(|card| card.suit == suit && card.rank as u8 == rank)
+                    .expect("each deck has one card per foundation of every rank");
+                foundation.push(Card { face_up: true, ..deck.swap_remove(index) });
+            }
+        }
+        
+        deck.shuffle(&mut StdRng::seed_from_u64(seed));
+        let mut cards = deck.into_iter();
+        'deal: for row in 0..rules.tableau_columns {
+            for col in row..rules.tableau_columns {
+                let Some(card) = cards.next() else { break 'deal };
+                game.tableau[col].push(Card { face_up: false, ..card });
+            }
+        }
+        for column in &mut game.tableau {
+            if let Some(top) = column.last_mut() {
+                top.face_up = true;
+            }
+        }
+        game.stock.extend(cards.map(|card| Card { face_up: false, ..card }));
+        
+        debug_assert!(game.deck_is_valid(), "endgame deal lost or duplicated a card");
+        game
+    }
+
+    // Deals an already-ordered deck in the standard Klondike pattern
+    pub fn deal(deck: Vec<Card>, seed: u64) -> Self {
+        Self::deal_with_rules(deck, seed, RulesConfig::default())
+    }
+
+    pub fn deal_with_rules(deck: Vec<Card>, seed: u64, rules: RulesConfig) -> Self {
+        let mut game = Self::empty(rules.validated(), seed);
+        let rules = game.rules;
         
         // Deal cards to tableau
         let mut deck_index = 0;
-        for col in 0..7 {
+        for col in 0..rules.tableau_columns {
             for row in 0..=col {
                 let mut card = deck[deck_index];
-                if row == col {
-                    card.face_up = true; // Top card of each column is face up
-                }
+                card.face_up = row == col; // Only the top card of each column is face up
                 game.tableau[col].push(card);
                 deck_index += 1;
             }
         }
         
         // Remaining cards go to stock
-        for i in deck_index..52 {
-            game.stock.push(deck[i]);
-        }
+        game.stock.extend(deck[deck_index..].iter().map(|&card| Card { face_up: false, ..card }));
         
+        debug_assert!(game.is_klondike_deal(), "deal left a buried card face up or the stock face up");
         game
     }
     
+    // The whole position as one line of text, for sharing a game part way through:
+    //   <draw count> <tableau> <stock> <waste> <foundations>
+    // The draw count is "A" in the draw-all easy mode, which draws the whole stock.
+    // Piles within a section are separated by '/' and list their cards bottom to top
+    // as two-character codes (see Card::code), with "-" for an empty pile. Foundations
+    // give only their top card, since everything below it follows from the suit.
+    pub fn to_position_string(&self) -> String {
+        let pile = |cards: &[Card]| match cards {
+            [] => "-".to_string(),
+            cards => cards.iter().map(Card::code).collect(),
+        };
+        let piles = |piles: &[Vec<Card>]| piles.iter().map(|cards| pile(cards)).collect::<Vec<_>>().join("/");
+        let foundation_tops: Vec<Vec<Card>> = self.foundations.iter()
+            .map(|foundation| foundation.last().into_iter().copied().collect())
+            .collect();
+        let draw = if self.rules.draw_all { "A".to_string() } else { self.draw_count.to_string() };
+        format!(
+            "{} {} {} {} {}",
+            draw,
+            piles(&self.tableau),
+            pile(&self.stock),
+            pile(&self.waste),
+            piles(&foundation_tops)
+        )
+    }
+    
+    // Loads a position written by to_position_string, checked as thoroughly as from_layout
+    pub fn from_position_string(text: &str, rules: RulesConfig) -> Result<Self, String> {
+        let sections: Vec<&str> = text.split_whitespace().collect();
+        let [draw, tableau, stock, waste, foundations] = sections[..] else {
+            return Err(format!("Expected 5 sections separated by spaces, got {}", sections.len()));
+        };
+        let (draw_count, draw_all) = match draw {
+            "1" => (1, rules.draw_all),
+            "3" => (3, rules.draw_all),
+            "A" => (3, true),
+            _ => return Err(format!("Invalid draw count: {} (expected 1, 3 or A)", draw)),
+        };
+        let rules = RulesConfig { draw_all, ..rules };
+        
+        let parse_pile = |pile: &str| -> Result<Vec<Card>, String> {
+            if pile == "-" {
+                return Ok(Vec::new());
+            }
+            if !pile.is_ascii() || !pile.len().is_multiple_of(2) {
+                return Err(format!("Cards must be two characters each: {}", pile));
+            }
+            (0..pile.len()).step_by(2)
+                .map(|i| Card::from_code(&pile[i..i + 2]).ok_or_else(|| format!("Unknown card: {}", &pile[i..i + 2])))
+                .collect()
+        };
+        let tableau = tableau.split('/').map(parse_pile).collect::<Result<Vec<_>, _>>()?;
+        let stock = parse_pile(stock)?;
+        let waste = parse_pile(waste)?;
+        let foundations = foundations.split('/')
+            .map(|pile| {
+                let top = match parse_pile(pile)?[..] {
+                    [] => return Ok(Vec::new()),
+                    [top] => top,
+                    _ => return Err(format!("Foundations give only their top card: {}", pile)),
+                };
+                Ok((1..=top.rank as u8)
+                    .filter_map(Rank::from_value)
+                    .map(|rank| Card { face_up: true, ..Card::new(top.suit, rank) })
+                    .collect())
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        
+        let mut game = Self::from_layout(tableau, stock, waste, foundations, rules)?;
+        game.draw_count = draw_count;
+        Ok(game)
+    }
+    
+    // Every card of the rules' deck is somewhere on the board, exactly as often as the deck has it
+    pub fn deck_is_valid(&self) -> bool {
+        let cards = self.tableau.iter().flatten()
+            .chain(&self.stock)
+            .chain(&self.waste)
+            .chain(self.foundations.iter().flatten());
+        deck_mismatch(&self.rules, cards).is_none()
+    }
+    
+    // Every column face down apart from its top card, and the whole stock face down
+    fn is_klondike_deal(&self) -> bool {
+        let columns_ok = self.tableau.iter().all(|column| {
+            column.iter().enumerate().all(|(row, card)| card.face_up == (row + 1 == column.len()))
+        });
+        columns_ok && self.stock.iter().all(|card| !card.face_up)
+    }
+    
+    // A position given pile by pile, for tests and puzzle setups. The piles must hold
+    // exactly one copy of the rules' deck. Tableau columns must be face-down cards under
+    // face-up ones, ending face up. The stock must be face down and the waste face up.
+    // Foundations must be built up by suit from the Ace.
+    pub fn from_layout(
+        tableau: Vec<Vec<Card>>,
+        stock: Vec<Card>,
+        waste: Vec<Card>,
+        foundations: Vec<Vec<Card>>,
+        rules: RulesConfig,
+    ) -> Result<Self, String> {
+        let rules = rules.validated();
+        if tableau.len() != rules.tableau_columns {
+            return Err(format!("Expected {} tableau columns, got {}", rules.tableau_columns, tableau.len()));
+        }
+        if foundations.len() != rules.foundations {
+            return Err(format!("Expected {} foundations, got {}", rules.foundations, foundations.len()));
+        }
+        
+        let given = tableau.iter().flatten()
+            .chain(&stock)
+            .chain(&waste)
+            .chain(foundations.iter().flatten());
+        if let Some((suit, rank, count)) = deck_mismatch(&rules, given) {
+            let name = format!("{}{}", rank.symbol(), suit.symbol());
+            return Err(if count > 0 {
+                format!("Not a full deck: {} is missing", name)
+            } else {
+                format!("Not a full deck: {} appears too often", name)
+            });
+        }
+        
+        for (col, column) in tableau.iter().enumerate() {
+            let face_up_from = column.iter().position(|card| card.face_up).unwrap_or(column.len());
+            if column[face_up_from..].iter().any(|card| !card.face_up) || (!column.is_empty() && face_up_from == column.len()) {
+                return Err(format!("Column {} must be face-down cards under face-up ones, ending face up", col + 1));
+            }
+        }
+        if stock.iter().any(|card| card.face_up) {
+            return Err("Stock cards must be face down".to_string());
+        }
+        if waste.iter().any(|card| !card.face_up) {
+            return Err("Waste cards must be face up".to_string());
+        }
+        for (f, foundation) in foundations.iter().enumerate() {
+            let suit = Self::foundation_suit(f);
+            let built = foundation.iter().enumerate().all(|(i, card)| {
+                card.face_up && card.suit == suit && card.rank as usize == i + 1
+            });
+            if !built {
+                return Err(format!("Foundation {} must run from the Ace of {} upwards, face up", f + 1, suit.name()));
+            }
+        }
+        
+        let mut game = Self::empty(rules, 0);
+        game.tableau = tableau;
+        game.stock = stock;
+        game.waste = waste;
+        game.foundations = foundations;
+        game.celebrated_foundations = game.foundations.iter().map(|f| f.len() == rules.foundation_size).collect();
+        Ok(game)
+    }
+    
+    // Puzzle practice: the same opening tableau every time, with the rest of the
+    // deck shuffled by `seed` into the stock, so only the luck of the draw changes.
+    // The columns must be laid out as from_layout expects.
+    pub fn with_fixed_tableau(tableau: Vec<Vec<Card>>, rules: RulesConfig, seed: u64) -> Result<Self, String> {
+        let rules = rules.validated();
+        let mut stock = rules.create_deck();
+        for card in tableau.iter().flatten() {
+            let Some(i) = stock.iter().position(|c| c.suit == card.suit && c.rank == card.rank) else {
+                return Err(format!("Not a full deck: {}{} appears too often", card.rank.symbol(), card.suit.symbol()));
+            };
+            stock.swap_remove(i);
+        }
+        stock.shuffle(&mut StdRng::seed_from_u64(seed));
+        for card in &mut stock {
+            card.face_up = false;
+        }
+        
+        let mut game = Self::from_layout(tableau, stock, Vec::new(), vec![Vec::new(); rules.foundations], rules)?;
+        game.seed = seed;
+        debug_assert!(game.deck_is_valid(), "fixed tableau and stock don't make one deck");
+        Ok(game)
+    }
+    
+    // No cards anywhere yet
+    fn empty(rules: RulesConfig, seed: u64) -> Self {
+        GameState {
+            tableau: vec![Vec::new(); rules.tableau_columns],
+            stock: Vec::new(),
+            waste: Vec::new(),
+            foundations: vec![Vec::new(); rules.foundations],
+            selected_card: None,
+            move_count: 0,
+            score: rules.starting_score(),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            draw_count: 3, // Default to draw 3
+            seed,
+            history: Vec::new(),
+            opening: None,
+            par: None,
+            timer: GameTimer::start(),
+            move_rejected: false,
+            status_message: None,
+            score_popup: None,
+            hint: None,
+            hint_cycle: 0,
+            cursor: None,
+            rules,
+            recycles: 0,
+            celebrated_foundations: vec![false; rules.foundations],
+            hints_used: 0,
+            peeked: false,
+            combo: 0,
+            event_hook: EventHook::default(),
+            strings: &ENGLISH,
+        }
+    }
+    
+    // Draws using the game's current draw mode
     pub fn draw_from_stock(&mut self) {
-        self.save_undo_state();
+        self.draw_cards(self.cards_per_draw());
+    }
+    
+    // The draw count, or the whole stock in the easy draw-all mode
+    pub fn cards_per_draw(&self) -> usize {
+        if self.rules.draw_all {
+            self.stock.len().max(1)
+        } else {
+            self.draw_count
+        }
+    }
+    
+    pub fn draw_one(&mut self) {
+        self.draw_cards(1);
+    }
+    
+    pub fn draw_three(&mut self) {
+        self.draw_cards(3);
+    }
+    
+    // Every draw and recycle goes through here
+    pub fn draw_cards(&mut self, count: usize) {
+        // Nothing to draw and nothing to turn over: no pass, penalty, move or undo step
+        if !self.can_draw() {
+            let reason = if self.waste.is_empty() { self.strings.stock_and_waste_empty } else { self.strings.no_passes_left };
+            self.reject_move(reason.to_string());
+            return;
+        }
+        
+        self.save_undo_entry(UndoKind::Draw);
+        self.record_step(Step::Draw(count));
+        self.combo = 0;
         
         if self.stock.is_empty() {
             // Flip waste back to stock
@@ -70,10 +473,12 @@ impl GameState {
                 card.face_up = false;
                 self.stock.push(card);
             }
-            self.score = (self.score - 20).max(0); // Penalty for recycling
+            self.recycles += 1;
+            self.add_score_at(-self.rules.recycle_penalty(), PileType::Stock, 0); // Penalty for recycling
+            self.emit(GameEvent::StockRecycled);
         } else {
             // Draw cards from stock to waste
-            let cards_to_draw = self.draw_count.min(self.stock.len());
+            let cards_to_draw = count.min(self.stock.len());
             for _ in 0..cards_to_draw {
                 if let Some(mut card) = self.stock.pop() {
                     card.face_up = true;
@@ -83,117 +488,591 @@ impl GameState {
         }
         
         self.move_count += 1;
+        self.move_made(None);
     }
     
-    pub fn is_valid_tableau_move(&self, card: &Card, target_col: usize) -> bool {
-        if self.tableau[target_col].is_empty() {
-            // Only Kings can go on empty columns
-            card.rank == Rank::King
-        } else {
-            let target_card = self.tableau[target_col].last().unwrap();
-            card.can_stack_on(target_card)
+    // Draws left before the stock runs out, and how many cards the last one turns over
+    pub fn stock_draws(&self) -> (usize, usize) {
+        let per_draw = self.cards_per_draw();
+        let draws = self.stock.len().div_ceil(per_draw);
+        let last = match self.stock.len() % per_draw {
+            0 if draws > 0 => per_draw,
+            short => short,
+        };
+        (draws, last)
+    }
+    
+    // One line describing the stock, waste and foundations, for reading without looking at the board
+    pub fn summary(&self) -> String {
+        let name = |card: Option<&Card>| match card {
+            Some(card) => format!("{}{}", card.rank.symbol(), card.suit.symbol()),
+            None => "empty".to_string(),
+        };
+        let foundations: Vec<String> = self.foundations.iter().map(|pile| name(pile.last())).collect();
+        format!(
+            "Stock: {} cards | Waste: {} | Foundations: {}",
+            self.stock.len(),
+            name(self.waste.last()),
+            foundations.join(" ")
+        )
+    }
+    
+    // Whether another pass through the stock is allowed
+    pub fn can_recycle(&self) -> bool {
+        self.rules.max_passes.is_none_or(|max| self.recycles + 1 < max)
+    }
+    
+    // Whether a draw would do anything: deal from the stock, or turn the waste over
+    pub fn can_draw(&self) -> bool {
+        !self.stock.is_empty() || (!self.waste.is_empty() && self.can_recycle())
+    }
+    
+    // The first column whose top card is still face down, which only happens
+    // when auto_flip is off and the player hasn't turned it over yet
+    pub fn face_down_top(&self) -> Option<usize> {
+        self.tableau.iter().position(|column| column.last().is_some_and(|card| !card.face_up))
+    }
+    
+    // Turns a column's face-down top card over for the usual flip points.
+    // Undoable like a move, though it isn't counted as one.
+    pub fn flip_top(&mut self, col: usize) -> bool {
+        if self.tableau[col].last().is_none_or(|card| card.face_up) {
+            return false;
         }
+        self.save_undo_state();
+        self.record_step(Step::Flip(col));
+        if let Some(card) = self.tableau[col].last_mut() {
+            card.face_up = true;
+        }
+        self.add_score_at(self.rules.flip_points(), PileType::Tableau, col);
+        true
+    }
+    
+    // Whether the next recycle begins the last pass the rules allow
+    pub fn recycle_starts_final_pass(&self) -> bool {
+        self.rules.max_passes.is_some_and(|max| self.recycles + 2 == max)
+    }
+    
+    // Whether drawing now would turn the waste back into the stock
+    pub fn draw_recycles(&self) -> bool {
+        self.stock.is_empty() && !self.waste.is_empty() && self.can_recycle()
+    }
+    
+    pub fn is_valid_tableau_move(&self, card: &Card, target_col: usize) -> bool {
+        self.check_tableau_move(card, target_col).is_ok()
     }
     
     pub fn is_valid_foundation_move(&self, card: &Card, foundation_idx: usize) -> bool {
-        if self.foundations[foundation_idx].is_empty() {
+        self.check_foundation_move(card, foundation_idx).is_ok()
+    }
+    
+    pub fn check_tableau_move(&self, card: &Card, target_col: usize) -> Result<(), MoveRejection> {
+        match self.tableau[target_col].last() {
+            // Only Kings (or the top rank of a short deck) can go on empty columns
+            None if card.rank == self.rules.highest_rank() => Ok(()),
+            None => Err(MoveRejection::EmptyNeedsKing),
+            Some(target) if !target.face_up => Err(MoveRejection::TargetFaceDown),
+            Some(target) if card.is_red() == target.is_red() => Err(MoveRejection::WrongColor),
+            Some(target) if !card.can_stack_on(target) => Err(MoveRejection::WrongRank),
+            Some(_) => Ok(()),
+        }
+    }
+    
+    // Each foundation slot builds one suit, in Suit::ALL order (repeating for a second deck)
+    pub fn foundation_suit(foundation_idx: usize) -> Suit {
+        Suit::ALL[foundation_idx % Suit::ALL.len()]
+    }
+    
+    // Every foundation holds only its own suit, so no suit is built in more slots than there are decks
+    fn foundations_keep_their_suits(&self) -> bool {
+        self.foundations.iter().enumerate().all(|(f, foundation)| {
+            foundation.iter().all(|card| card.suit == Self::foundation_suit(f))
+        })
+    }
+    
+    pub fn check_foundation_move(&self, card: &Card, foundation_idx: usize) -> Result<(), MoveRejection> {
+        match self.foundations[foundation_idx].last() {
+            _ if card.suit != Self::foundation_suit(foundation_idx) => Err(MoveRejection::FoundationWrongSuit),
             // Only Aces can start a foundation
-            card.rank == Rank::Ace
-        } else {
-            let top_card = self.foundations[foundation_idx].last().unwrap();
+            None if card.rank == Rank::Ace => Ok(()),
+            None => Err(MoveRejection::FoundationNeedsAce),
             // Must be same suit and one rank higher
-            card.suit == top_card.suit && card.rank as u8 == top_card.rank as u8 + 1
+            Some(top) if card.suit != top.suit => Err(MoveRejection::FoundationWrongSuit),
+            Some(top) if card.rank as u8 != top.rank as u8 + 1 => Err(MoveRejection::FoundationOutOfOrder),
+            Some(_) => Ok(()),
         }
     }
     
-    pub fn auto_move_to_foundation(&mut self) -> bool {
-        let mut moved = false;
-        
-        // Check waste pile
-        if let Some(card) = self.waste.last() {
-            for f in 0..4 {
-                if self.is_valid_foundation_move(card, f) {
-                    self.save_undo_state();
-                    let card = self.waste.pop().unwrap();
-                    self.foundations[f].push(card);
-                    self.score += 10;
-                    moved = true;
-                    break;
-                }
+    pub fn explain_tableau_move(&self, card: &Card, target_col: usize) -> String {
+        match (self.check_tableau_move(card, target_col), self.tableau[target_col].last()) {
+            (Err(MoveRejection::EmptyNeedsKing), _) if self.rules.highest_rank() == Rank::King => {
+                "Only a King can move to an empty column".to_string()
+            }
+            (Err(MoveRejection::EmptyNeedsKing), _) => {
+                format!("Only a {} can move to an empty column", self.rules.highest_rank().symbol())
+            }
+            (Err(MoveRejection::TargetFaceDown), _) => {
+                format!("Column {}'s top card is face down: press {} to turn it over", target_col + 1, target_col + 1)
+            }
+            (Err(MoveRejection::WrongColor), Some(target)) => {
+                format!("{} can't go on {}: colors must alternate", card, target)
+            }
+            (_, Some(target)) => format!(
+                "Column {} needs a {} {} next",
+                target_col + 1,
+                if target.is_red() { "black" } else { "red" },
+                Rank::from_value(target.rank as u8 - 1).map_or("card", |r| r.symbol())
+            ),
+            (_, None) => format!("{} can go on column {}", card, target_col + 1),
+        }
+    }
+    
+    pub fn explain_foundation_move(&self, card: &Card, foundation_idx: usize) -> String {
+        let suit = Self::foundation_suit(foundation_idx);
+        let Some(top) = self.foundations[foundation_idx].last() else {
+            return if card.suit != suit {
+                format!("{} can't go on the {} foundation", card, suit.name())
+            } else {
+                format!("{} can't start a foundation: Aces only", card)
+            };
+        };
+        match (self.check_foundation_move(card, foundation_idx), Rank::from_value(top.rank as u8 + 1)) {
+            _ if top.rank == self.rules.highest_rank() => "That foundation is already complete".to_string(),
+            (Err(MoveRejection::FoundationWrongSuit), _) => {
+                format!("{} can't go on the {} foundation", card, top.suit.name())
+            }
+            (_, Some(next)) => format!("Foundation needs the {}{} next", next.symbol(), top.suit.symbol()),
+            (_, None) => "That foundation is already complete".to_string(),
+        }
+    }
+    
+    pub fn first_face_up(&self, col: usize) -> Option<usize> {
+        self.tableau[col].iter().position(|card| card.face_up)
+    }
+    
+    // Read-only views for bots and other programs watching a game. They borrow
+    // or copy rather than allocate, so they are cheap to call after every move.
+    
+    // The top card of each foundation in slot order, None for an empty one
+    pub fn foundation_tops(&self) -> impl Iterator<Item = Option<Card>> + '_ {
+        self.foundations.iter().map(|foundation| foundation.last().copied())
+    }
+    
+    // The one waste card that can be played, if any
+    pub fn playable_waste_card(&self) -> Option<Card> {
+        self.waste.last().copied()
+    }
+    
+    // A column's face-up cards, bottom to top; empty if it has none
+    pub fn face_up_run(&self, col: usize) -> &[Card] {
+        let column = &self.tableau[col];
+        &column[self.first_face_up(col).unwrap_or(column.len())..]
+    }
+    
+    // Every column's face-up run, in column order
+    pub fn face_up_runs(&self) -> impl Iterator<Item = &[Card]> + '_ {
+        (0..self.tableau.len()).map(|col| self.face_up_run(col))
+    }
+    
+    // Cards left to draw before the waste has to be turned over
+    pub fn stock_count(&self) -> usize {
+        self.stock.len()
+    }
+    
+    pub fn describe_selection(&self) -> Option<String> {
+        let (pile, col, row) = self.selected_card?;
+        if pile != PileType::Tableau {
+            return None;
+        }
+        let count = self.tableau[col].len().saturating_sub(row);
+        let card = self.tableau[col].get(row)?;
+        Some(format!(
+            "Selected {} in column {} ({} card{})",
+            card,
+            col + 1,
+            count,
+            if count == 1 { "" } else { "s" }
+        ))
+    }
+    
+    // Adds to the history, first noting where it starts. Called before the
+    // step changes anything, like the undo snapshot.
+    pub fn record_step(&mut self, step: Step) {
+        if self.history.is_empty() {
+            self.opening = Some(self.to_position_string());
+        }
+        self.history.push(step);
+    }
+    
+    pub fn clear_hint(&mut self) {
+        self.hint = None;
+        self.hint_cycle = 0;
+    }
+    
+    pub fn show_status(&mut self, message: String) {
+        self.status_message = Some((message, Instant::now()));
+    }
+    
+    pub fn set_event_hook(&mut self, hook: impl Fn(&GameEvent) + 'static) {
+        self.event_hook = EventHook(Some(Rc::new(hook)));
+    }
+    
+    pub fn clear_event_hook(&mut self) {
+        self.event_hook = EventHook::default();
+    }
+    
+    fn emit(&self, event: GameEvent) {
+        if let Some(hook) = &self.event_hook.0 {
+            hook(&event);
+        }
+    }
+    
+    // Reports a finished move, and whatever it completed if it went to a foundation
+    pub fn move_made(&self, to_foundation: Option<usize>) {
+        debug_assert!(self.foundations_keep_their_suits(), "a foundation holds the wrong suit");
+        self.emit(GameEvent::MoveMade);
+        if let Some(f) = to_foundation.filter(|&f| self.foundations[f].len() == self.rules.foundation_size) {
+            self.emit(GameEvent::FoundationCompleted(f));
+            if self.is_won() {
+                self.emit(GameEvent::GameWon);
             }
         }
+    }
+    
+    // Extends the combo and returns what this foundation move is worth
+    pub fn foundation_move_points(&mut self) -> i32 {
+        self.combo += 1;
+        self.rules.foundation_points() * self.rules.combo_multiplier(self.combo)
+    }
+    
+    // The most this game can still finish on: every card left goes up (keeping any combo
+    // going), every face-down card is turned over, and each stock and waste card earns
+    // one tableau move on its way. Moving runs back and forth between columns for
+    // points isn't counted, and penalties already paid stay paid.
+    pub fn score_ceiling(&self) -> i32 {
+        let in_foundations: usize = self.foundations.iter().map(Vec::len).sum();
+        let to_go = (self.card_count() - in_foundations) as u32;
+        let face_down = self.tableau.iter().flatten().filter(|card| !card.face_up).count() as i32;
+        let in_hand = (self.stock.len() + self.waste.len()) as i32;
+        let foundation: i32 = (1..=to_go)
+            .map(|i| self.rules.foundation_points() * self.rules.combo_multiplier(self.combo + i))
+            .sum();
+        self.score + foundation + face_down * self.rules.flip_points() + in_hand * self.rules.tableau_points()
+    }
+    
+    // Peeking is for practice, so it isn't offered where the score or move count is the point
+    pub fn peek_allowed(&self) -> bool {
+        self.rules.scoring == ScoringMode::Standard && self.rules.max_moves.is_none()
+    }
+    
+    // Counts a hint and charges for it, if the rules say hints cost points
+    pub fn record_hint(&mut self) {
+        self.hints_used += 1;
+        self.add_score(-self.rules.hint_cost());
+    }
+    
+    // Every score change goes through here, so none can take it below the rules' floor
+    pub fn add_score(&mut self, delta: i32) {
+        self.score += delta;
+        if let Some(floor) = self.rules.score_floor() {
+            self.score = self.score.max(floor);
+        }
+    }
+    
+    // Like add_score, but remembers what actually changed and where, for the display to show by that pile
+    pub fn add_score_at(&mut self, delta: i32, pile_type: PileType, index: usize) {
+        let before = self.score;
+        self.add_score(delta);
+        if self.score != before {
+            self.score_popup = Some((self.score - before, pile_type, index, Instant::now()));
+        }
+    }
+    
+    pub fn reject_move(&mut self, reason: String) {
+        self.move_rejected = true;
+        self.show_status(reason);
+    }
+    
+    // Sends the waste card, or failing that the first tableau card that fits, to a
+    // foundation. Returns the move made so callers can show or record it.
+    pub fn auto_move_to_foundation(&mut self) -> Option<Move> {
+        let waste = self.waste.len().checked_sub(1).map(|top| (PileType::Waste, 0, top));
+        let columns = (0..self.rules.tableau_columns)
+            .filter_map(|col| self.tableau[col].len().checked_sub(1).map(|top| (PileType::Tableau, col, top)));
+        let sources: Vec<_> = waste.into_iter().chain(columns).collect();
         
-        // Check tableau columns
-        if !moved {
-            for col in 0..7 {
-                if !self.tableau[col].is_empty() {
-                    if let Some(card) = self.tableau[col].last() {
-                        if card.face_up {
-                            for f in 0..4 {
-                                if self.is_valid_foundation_move(card, f) {
-                                    self.save_undo_state();
-                                    let card = self.tableau[col].pop().unwrap();
-                                    self.foundations[f].push(card);
-                                    
-                                    // Flip the new top card if needed
-                                    if let Some(new_top) = self.tableau[col].last_mut() {
-                                        if !new_top.face_up {
-                                            new_top.face_up = true;
-                                            self.score += 5;
-                                        }
-                                    }
-                                    
-                                    self.score += 10;
-                                    moved = true;
-                                    break;
-                                }
-                            }
-                        }
+        for (pile_type, pile_index, card_index) in sources {
+            let card = match pile_type {
+                PileType::Waste => self.waste[card_index],
+                _ => self.tableau[pile_index][card_index],
+            };
+            if !card.face_up {
+                continue;
+            }
+            for f in 0..self.foundations.len() {
+                if self.is_valid_foundation_move(&card, f) {
+                    let from = MoveLocation { pile_type, pile_index, card_index };
+                    let to = MoveLocation {
+                        pile_type: PileType::Foundation,
+                        pile_index: f,
+                        card_index: self.foundations[f].len(),
+                    };
+                    let mut mv = Move::new(from, to, vec![card]);
+                    if mv.execute(self) {
+                        return Some(mv);
                     }
-                    if moved { break; }
                 }
             }
         }
         
-        self.move_count += if moved { 1 } else { 0 };
-        moved
+        None
+    }
+    
+    // Foundations finished since the last call. Each is reported once per game,
+    // even if undo takes its top card back off and it is completed again.
+    pub fn newly_completed_foundations(&mut self) -> Vec<usize> {
+        let mut completed = Vec::new();
+        for (f, foundation) in self.foundations.iter().enumerate() {
+            if foundation.len() == self.rules.foundation_size && !self.celebrated_foundations[f] {
+                self.celebrated_foundations[f] = true;
+                completed.push(f);
+            }
+        }
+        completed
+    }
+    
+    // Cards in play across every pile
+    pub fn card_count(&self) -> usize {
+        self.tableau.iter().map(Vec::len).sum::<usize>()
+            + self.stock.len()
+            + self.waste.len()
+            + self.foundations.iter().map(Vec::len).sum::<usize>()
+    }
+    
+    // Nothing left to uncover or draw, so foundation moves alone finish the game
+    pub fn can_auto_complete(&self) -> bool {
+        self.stock.is_empty()
+            && self.waste.is_empty()
+            && self.tableau.iter().flatten().all(|card| card.face_up)
+            && !self.is_won()
+    }
+    
+    // Won in all but the playing: either auto-complete can finish it, or the stock
+    // is used up and the solver finds a win within a small budget, face-down
+    // cards and all. Cheap enough to ask after every move.
+    pub fn is_trivially_won(&self) -> bool {
+        if self.can_auto_complete() {
+            return true;
+        }
+        self.stock.is_empty()
+            && self.waste.is_empty()
+            && !self.is_won()
+            && solve(self, TRIVIAL_WIN_BUDGET) == SolveResult::Winnable
+    }
+    
+    // Challenge mode's move budget is spent
+    pub fn out_of_moves(&self) -> bool {
+        self.rules.max_moves.is_some_and(|max| self.move_count >= max)
+    }
+    
+    // Why play stopped, worked out from where the game was left
+    pub fn game_over_reason(&self) -> GameOverReason {
+        if self.is_won() {
+            GameOverReason::Won
+        } else if self.out_of_moves() {
+            GameOverReason::MoveLimit
+        } else if is_stuck(self) {
+            GameOverReason::Stuck
+        } else {
+            GameOverReason::Quit
+        }
+    }
+    
+    pub fn position_key(&self) -> PositionKey {
+        let mut key = Vec::with_capacity(self.card_count() + 16);
+        let mut push_pile = |pile: &[Card]| {
+            for card in pile {
+                key.push((card.suit as u8) << 4 | card.rank as u8 | if card.face_up { 0x80 } else { 0 });
+            }
+            key.push(0xFF); // Pile boundary, so the same cards split differently don't collide
+        };
+        for column in &self.tableau {
+            push_pile(column);
+        }
+        push_pile(&self.stock);
+        push_pile(&self.waste);
+        for foundation in &self.foundations {
+            push_pile(foundation);
+        }
+        PositionKey(key)
+    }
+    
+    // Same cards in the same places, however each game got there
+    pub fn same_position(&self, other: &GameState) -> bool {
+        self.position_key() == other.position_key()
     }
     
     pub fn is_won(&self) -> bool {
-        self.foundations.iter().all(|f| f.len() == 13)
+        self.foundations.iter().all(|f| f.len() == self.rules.foundation_size)
+    }
+    
+    pub fn elapsed_secs(&self) -> u64 {
+        self.timer.elapsed().as_secs()
+    }
+
+    pub fn append_result_csv<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let path = path.as_ref();
+        let needs_header = std::fs::metadata(path).map(|m| m.len() == 0).unwrap_or(true);
+
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if needs_header {
+            writeln!(file, "timestamp,seed,result,score,moves,time_secs,mode")?;
+        }
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        writeln!(
+            file,
+            "{},{},{},{},{},{},draw-{}{}{}",
+            timestamp,
+            self.seed,
+            if self.is_won() { "won" } else { "lost" },
+            self.score,
+            self.move_count,
+            self.elapsed_secs(),
+            self.draw_count,
+            if self.rules.scoring == ScoringMode::Vegas { "-vegas" } else { "" },
+            if self.peeked { "-peeked" } else { "" }
+        )
     }
     
+    // Snapshot taken before moving cards
     pub fn save_undo_state(&mut self) {
-        // Keep only last 100 states to avoid memory issues
-        if self.undo_stack.len() >= 100 {
+        self.save_undo_entry(UndoKind::Move);
+    }
+    
+    pub fn save_undo_entry(&mut self, kind: UndoKind) {
+        self.redo_stack.clear(); // A new action starts a new line of play, so nothing is left to redo
+        
+        // The snapshot from before the run's first card already covers the rest of it
+        if kind == UndoKind::AutoComplete && self.undo_stack.last().is_some_and(|entry| entry.kind == kind) {
+            return;
+        }
+        
+        // Oldest snapshots go first; with no room at all, nothing is copied
+        let capacity = self.undo_capacity();
+        if capacity == 0 {
+            return;
+        }
+        while self.undo_stack.len() >= capacity {
             self.undo_stack.remove(0);
         }
         
+        let entry = self.snapshot(kind);
+        self.undo_stack.push(entry);
+    }
+    
+    // Snapshots undo may keep: the rules' limit, lowered for boards so big that
+    // that many copies would pass UNDO_MEMORY_BUDGET. 0 means undo is off.
+    pub fn undo_capacity(&self) -> usize {
+        let snapshot_bytes = std::mem::size_of::<GameState>() + self.card_count() * std::mem::size_of::<Card>();
+        self.rules.undo_limit.min(UNDO_MEMORY_BUDGET / snapshot_bytes)
+    }
+    
+    // Why Z does nothing, when it's because undo is off rather than there being nothing to undo
+    pub fn undo_off_reason(&self) -> Option<&'static str> {
+        match self.undo_capacity() {
+            _ if self.rules.undo_limit == 0 => Some("Undo is turned off (undo_limit is 0)"),
+            0 => Some("Undo is off: this board is too big to keep copies of"),
+            _ => None,
+        }
+    }
+    
+    fn snapshot(&self, kind: UndoKind) -> UndoEntry {
         let mut state_copy = self.clone();
         state_copy.undo_stack.clear(); // Don't store undo stack in undo stack
-        self.undo_stack.push(state_copy);
+        state_copy.redo_stack.clear();
+        UndoEntry { state: state_copy, kind }
     }
     
     pub fn undo(&mut self) -> bool {
-        if let Some(previous_state) = self.undo_stack.pop() {
-            let undo_stack = self.undo_stack.clone();
-            *self = previous_state;
-            self.undo_stack = undo_stack;
+        if let Some(previous) = self.undo_stack.pop() {
+            let current = self.snapshot(previous.kind);
+            self.restore(previous.state);
+            self.redo_stack.push(current);
             true
         } else {
             false
         }
     }
     
+    // Steps forward again through what undo took back
+    pub fn redo(&mut self) -> bool {
+        if let Some(next) = self.redo_stack.pop() {
+            let current = self.snapshot(next.kind);
+            self.restore(next.state);
+            self.undo_stack.push(current);
+            true
+        } else {
+            false
+        }
+    }
+    
+    // Puts the board back as it was in a snapshot, keeping what belongs to the whole game
+    fn restore(&mut self, snapshot: GameState) {
+        // Snapshots are taken before a move changes anything, so no card can go missing
+        debug_assert_eq!(snapshot.card_count(), self.card_count(), "undo snapshot lost cards");
+        let undo_stack = std::mem::take(&mut self.undo_stack);
+        let redo_stack = std::mem::take(&mut self.redo_stack);
+        let timer = self.timer;
+        let celebrated = std::mem::take(&mut self.celebrated_foundations);
+        let hints_used = self.hints_used;
+        let peeked = self.peeked;
+        let cursor = self.cursor;
+        *self = snapshot;
+        self.peeked = peeked;
+        self.cursor = cursor;
+        self.undo_stack = undo_stack;
+        self.redo_stack = redo_stack;
+        self.timer = timer; // Undo rewinds the board, not the clock
+        self.celebrated_foundations = celebrated; // and never re-arms a celebration
+        self.score_popup = None; // nor shows points from before the snapshot again
+        
+        // Hints taken since the snapshot stay counted and paid for, exactly once
+        let hints_since = hints_used - self.hints_used;
+        self.hints_used = hints_used;
+        self.add_score(-self.rules.hint_cost() * hints_since as i32);
+    }
+    
+    pub fn last_action_was_draw(&self) -> bool {
+        self.undo_stack.last().is_some_and(|entry| entry.kind == UndoKind::Draw)
+    }
+    
+    // Rewinds every card move made since the last draw or recycle, or the
+    // whole game if there hasn't been one. Returns false if nothing changed.
+    pub fn undo_to_last_draw(&mut self) -> bool {
+        let mut undone = false;
+        while !self.undo_stack.is_empty() && !self.last_action_was_draw() {
+            undone |= self.undo();
+        }
+        undone
+    }
+    
     pub fn get_hint(&self) -> Option<String> {
+        self.get_hint_with(&ENGLISH)
+    }
+    
+    pub fn get_hint_with(&self, strings: &Strings) -> Option<String> {
         // Check for moves to foundation
-        for col in 0..7 {
+        for col in 0..self.rules.tableau_columns {
             if !self.tableau[col].is_empty() {
                 if let Some(card) = self.tableau[col].last() {
                     if card.face_up {
-                        for f in 0..4 {
+                        for f in 0..self.foundations.len() {
                             if self.is_valid_foundation_move(card, f) {
-                                return Some(format!("Move {} from column {} to foundation", card, col + 1));
+                                return Some(format!("{} {} {} {} {}", strings.move_card, card, strings.from_column, col + 1, strings.to_foundation));
                             }
                         }
                     }
@@ -202,7 +1081,7 @@ impl GameState {
         }
         
         // Check for tableau to tableau moves
-        for from_col in 0..7 {
+        for from_col in 0..self.rules.tableau_columns {
             if !self.tableau[from_col].is_empty() {
                 // Find the lowest face-up card
                 let mut from_idx = 0;
@@ -215,10 +1094,10 @@ impl GameState {
                 
                 let card = &self.tableau[from_col][from_idx];
                 
-                for to_col in 0..7 {
+                for to_col in 0..self.rules.tableau_columns {
                     if from_col != to_col && self.is_valid_tableau_move(card, to_col) {
-                        return Some(format!("Move {} from column {} to column {}", 
-                                          card, from_col + 1, to_col + 1));
+                        return Some(format!("{} {} {} {} {} {}", strings.move_card, card,
+                                          strings.from_column, from_col + 1, strings.to_column, to_col + 1));
                     }
                 }
             }
@@ -226,17 +1105,342 @@ impl GameState {
         
         // Check waste pile
         if let Some(card) = self.waste.last() {
-            for col in 0..7 {
+            for col in 0..self.rules.tableau_columns {
                 if self.is_valid_tableau_move(card, col) {
-                    return Some(format!("Move {} from waste to column {}", card, col + 1));
+                    return Some(format!("{} {} {} {} {}", strings.move_card, card, strings.from_waste, strings.to_column, col + 1));
                 }
             }
         }
         
-        if !self.stock.is_empty() || !self.waste.is_empty() {
-            return Some("Draw from stock".to_string());
+        if !self.stock.is_empty() || (!self.waste.is_empty() && self.can_recycle()) {
+            return Some(strings.draw_from_stock.to_string());
         }
         
         None
     }
-}
\ No newline at end of file
+}
+
+// A card the given piles hold too few (positive count) or too many (negative)
+// copies of, compared with the rules' deck
+fn deck_mismatch<'a>(rules: &RulesConfig, cards: impl Iterator<Item = &'a Card>) -> Option<(Suit, Rank, i32)> {
+    let mut counts: HashMap<(Suit, Rank), i32> = HashMap::new();
+    for card in rules.create_deck() {
+        *counts.entry((card.suit, card.rank)).or_default() += 1;
+    }
+    for card in cards {
+        *counts.entry((card.suit, card.rank)).or_default() -= 1;
+    }
+    counts.into_iter()
+        .find(|&(_, count)| count != 0)
+        .map(|((suit, rank), count)| (suit, rank, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moves::Move;
+    use crate::rules::ScoringConfig;
+
+    // Cards from position-string codes run together, e.g. "2c7H"
+    fn cards(codes: &str) -> Vec<Card> {
+        codes.as_bytes()
+            .chunks(2)
+            .map(|code| Card::from_code(std::str::from_utf8(code).unwrap()).unwrap())
+            .collect()
+    }
+
+    // Column 1 holds the given cards, column 2 the other ones, the rest are empty
+    fn two_columns(first: &str, second: &str) -> GameState {
+        let mut tableau = vec![Vec::new(); 7];
+        tableau[0] = cards(first);
+        tableau[1] = cards(second);
+        GameState::with_fixed_tableau(tableau, RulesConfig::default(), 1).unwrap()
+    }
+
+    fn location(pile_type: PileType, pile_index: usize, card_index: usize) -> MoveLocation {
+        MoveLocation { pile_type, pile_index, card_index }
+    }
+
+    #[test]
+    fn same_colour_is_wrong_color() {
+        let game = two_columns("7H", "8D");
+        assert_eq!(game.check_tableau_move(&game.tableau[0][0], 1), Err(MoveRejection::WrongColor));
+    }
+
+    #[test]
+    fn skipping_a_rank_is_wrong_rank() {
+        let game = two_columns("6S", "8D");
+        assert_eq!(game.check_tableau_move(&game.tableau[0][0], 1), Err(MoveRejection::WrongRank));
+    }
+
+    #[test]
+    fn empty_column_needs_a_king() {
+        let game = two_columns("QS", "KD");
+        assert_eq!(game.check_tableau_move(&game.tableau[0][0], 2), Err(MoveRejection::EmptyNeedsKing));
+        assert_eq!(game.check_tableau_move(&game.tableau[1][0], 2), Ok(()));
+    }
+
+    #[test]
+    fn foundation_slot_turns_away_other_suits() {
+        let game = two_columns("AS", "8D");
+        // Slot 0 builds hearts
+        assert_eq!(game.check_foundation_move(&game.tableau[0][0], 0), Err(MoveRejection::FoundationWrongSuit));
+    }
+
+    #[test]
+    fn hearts_can_only_start_in_the_hearts_slot() {
+        let mut game = two_columns("AH", "8D");
+        let ace = game.tableau[0][0];
+        for slot in 1..4 {
+            assert_eq!(game.check_foundation_move(&ace, slot), Err(MoveRejection::FoundationWrongSuit), "slot {}", slot + 1);
+        }
+        let mut mv = Move::new(location(PileType::Tableau, 0, 0), location(PileType::Foundation, 1, 0), vec![ace]);
+        assert!(!mv.execute(&mut game), "a second hearts foundation must not be started");
+        assert!(game.foundations[1].is_empty());
+        assert_eq!(game.check_foundation_move(&ace, 0), Ok(()));
+    }
+
+    #[test]
+    fn empty_foundation_needs_an_ace() {
+        let game = two_columns("2H", "8D");
+        assert_eq!(game.check_foundation_move(&game.tableau[0][0], 0), Err(MoveRejection::FoundationNeedsAce));
+    }
+
+    #[test]
+    fn foundation_builds_one_rank_at_a_time() {
+        let mut game = two_columns("3H", "8D");
+        game.foundations[0] = cards("AH");
+        assert_eq!(game.check_foundation_move(&game.tableau[0][0], 0), Err(MoveRejection::FoundationOutOfOrder));
+    }
+
+    #[test]
+    fn face_down_top_card_takes_nothing() {
+        let mut game = two_columns("7H", "8S");
+        game.tableau[1][0].face_up = false;
+        assert_eq!(game.check_tableau_move(&game.tableau[0][0], 1), Err(MoveRejection::TargetFaceDown));
+    }
+
+    #[test]
+    fn standard_score_stops_at_zero() {
+        let points = ScoringConfig { hint_penalty: 5, ..ScoringConfig::STANDARD };
+        let mut game = GameState::new_with_rules(RulesConfig { points, ..RulesConfig::default() }, 1);
+        assert_eq!(game.score, 0);
+        while !game.stock.is_empty() {
+            game.draw_from_stock();
+        }
+        assert!(game.draw_recycles());
+        game.draw_from_stock();
+        assert!(game.waste.is_empty(), "the waste went back to the stock");
+        assert_eq!(game.score, 0, "the recycle penalty can't take the score below zero");
+        game.record_hint();
+        assert_eq!(game.score, 0, "nor can a hint");
+    }
+
+    #[test]
+    fn moves_score_what_the_config_says() {
+        let points = ScoringConfig { tableau: 7, foundation: 11, flip: 3, ..ScoringConfig::STANDARD };
+        let rules = RulesConfig { points, ..RulesConfig::default() };
+        let mut tableau = vec![Vec::new(); 7];
+        tableau[0] = cards("kd6H");
+        tableau[1] = cards("7S");
+        tableau[2] = cards("AH");
+        let mut game = GameState::with_fixed_tableau(tableau, rules, 1).unwrap();
+
+        let mut onto_tableau = Move::new(location(PileType::Tableau, 0, 1), location(PileType::Tableau, 1, 1), cards("6H"));
+        assert!(onto_tableau.execute(&mut game));
+        assert_eq!(game.score, 7 + 3, "a tableau move that uncovers a card");
+
+        let mut onto_foundation = Move::new(location(PileType::Tableau, 2, 0), location(PileType::Foundation, 0, 0), cards("AH"));
+        assert!(onto_foundation.execute(&mut game));
+        assert_eq!(game.score, 10 + 11);
+    }
+
+    #[test]
+    fn vegas_score_keeps_its_debt() {
+        let mut rules = RulesConfig::default().vegas(3);
+        rules.points.hint_penalty = 5;
+        let mut game = GameState::new_with_rules(rules, 1);
+        assert_eq!(game.score, -52, "a point owed per card");
+        game.record_hint();
+        assert_eq!(game.score, -57);
+    }
+
+    #[test]
+    fn undo_turns_a_revealed_card_back_down() {
+        for to in [location(PileType::Tableau, 1, 1), location(PileType::Foundation, 0, 0)] {
+            let mut game = two_columns("2cAH", "2S");
+            let score = game.score;
+            let card = game.tableau[0][1];
+            let mut mv = Move::new(location(PileType::Tableau, 0, 1), to, vec![card]);
+            assert!(mv.execute(&mut game));
+            assert!(game.tableau[0][0].face_up, "moving the only face-up card reveals the one under it");
+            assert!(game.score > score);
+
+            assert!(game.undo());
+            assert!(!game.tableau[0][0].face_up, "undo must turn the revealed card face down again");
+            assert_eq!(game.tableau[0], cards("2cAH"));
+            assert_eq!(game.score, score);
+        }
+    }
+
+    #[test]
+    fn position_strings_round_trip() {
+        // Play on until every kind of pile has cards, face down and face up
+        let mut game = GameState::new_with_seed(3);
+        while game.foundations.iter().all(Vec::is_empty) || game.waste.is_empty() {
+            match crate::moves::hint_moves(&game).into_iter().next() {
+                Some(mut mv) => assert!(mv.execute(&mut game)),
+                None => game.draw_from_stock(),
+            }
+        }
+        assert!(!game.stock.is_empty());
+        assert!(game.tableau.iter().flatten().any(|card| !card.face_up));
+        assert!(game.tableau.iter().flatten().any(|card| card.face_up));
+
+        let text = game.to_position_string();
+        let loaded = GameState::from_position_string(&text, game.rules).unwrap();
+        assert!(loaded.same_position(&game), "face-up flags and piles must survive the trip");
+        assert_eq!(loaded.draw_count, game.draw_count);
+        assert_eq!(loaded.to_position_string(), text);
+    }
+
+    #[test]
+    fn position_strings_keep_draw_all() {
+        let rules = RulesConfig { draw_all: true, ..RulesConfig::default() };
+        let game = GameState::new_with_rules(rules, 3);
+        let text = game.to_position_string();
+        assert!(text.starts_with("A "), "{}", text);
+        let loaded = GameState::from_position_string(&text, RulesConfig::default()).unwrap();
+        assert!(loaded.rules.draw_all, "the position alone is enough to restore draw-all");
+        assert_eq!(loaded.cards_per_draw(), loaded.stock.len());
+        assert_eq!(loaded.to_position_string(), text);
+    }
+
+    #[test]
+    fn malformed_position_strings_are_errors() {
+        let text = GameState::new_with_seed(3).to_position_string();
+        let sections: Vec<&str> = text.split(' ').collect();
+        let with = |section: usize, replacement: String| {
+            let mut changed: Vec<String> = sections.iter().map(|s| s.to_string()).collect();
+            changed[section] = replacement;
+            changed.join(" ")
+        };
+        let stock = sections[2];
+        let bad = [
+            with(1, format!("ZZ{}", &sections[1][2..])),                  // Not a card
+            with(2, stock[2..].to_string()),                              // One card short
+            with(2, format!("{}{}{}", &stock[..2], &stock[..2], &stock[4..])), // A card twice
+            "3 KH".to_string(),
+            String::new(),
+        ];
+        for position in bad {
+            assert!(GameState::from_position_string(&position, RulesConfig::default()).is_err(), "{:?} was accepted", position);
+        }
+    }
+
+    #[test]
+    fn every_shuffle_deals_one_whole_deck() {
+        for seed in 0..10_000 {
+            let game = GameState::new_with_seed(seed);
+            let all = game.tableau.iter().flatten().chain(&game.stock).chain(&game.waste);
+            let unique: std::collections::HashSet<(Suit, Rank)> = all.map(|card| (card.suit, card.rank)).collect();
+            assert_eq!(game.card_count(), 52, "seed {}", seed);
+            assert_eq!(unique.len(), 52, "seed {} repeated a card", seed);
+            assert!(game.deck_is_valid(), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn deals_show_only_each_columns_top_card() {
+        for seed in 0..100 {
+            let game = GameState::new_with_seed(seed);
+            for (col, column) in game.tableau.iter().enumerate() {
+                assert_eq!(column.len(), col + 1);
+                assert_eq!(column.iter().filter(|card| card.face_up).count(), 1, "seed {} column {}", seed, col + 1);
+                assert!(column.last().unwrap().face_up, "seed {} column {} must end face up", seed, col + 1);
+            }
+            assert!(game.stock.iter().all(|card| !card.face_up), "seed {} has a face-up stock card", seed);
+            assert!(game.waste.is_empty());
+        }
+    }
+
+    #[test]
+    fn narrow_and_wide_tableaus_deal_and_play() {
+        for columns in [5, 9] {
+            let rules = RulesConfig { tableau_columns: columns, ..RulesConfig::default() };
+            let mut game = GameState::new_with_rules(rules, 7);
+            assert_eq!(game.tableau.len(), columns);
+            for (col, column) in game.tableau.iter().enumerate() {
+                assert_eq!(column.len(), col + 1, "{} columns", columns);
+                assert!(column.last().unwrap().face_up, "{} columns: column {} must end face up", columns, col + 1);
+            }
+            assert_eq!(game.stock.len(), 52 - columns * (columns + 1) / 2);
+
+            // Play a while, checking every move offered stays inside the tableau
+            for _ in 0..200 {
+                let moves = crate::moves::find_valid_moves(&game);
+                for mv in &moves {
+                    for loc in [&mv.from, &mv.to] {
+                        if loc.pile_type == PileType::Tableau {
+                            assert!(loc.pile_index < columns, "{} columns: move touches column {}", columns, loc.pile_index + 1);
+                        }
+                    }
+                }
+                match moves.into_iter().next() {
+                    Some(mut mv) => assert!(mv.execute(&mut game)),
+                    None if game.can_draw() => game.draw_from_stock(),
+                    None => break,
+                }
+            }
+        }
+    }
+
+    fn hash_of(key: &PositionKey) -> u64 {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        key.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn position_keys_ignore_everything_but_the_cards() {
+        let game = GameState::new_with_seed(5);
+        let mut same = game.clone();
+        same.score += 40;
+        same.move_count = 12;
+        same.save_undo_state();
+        same.save_undo_state();
+        same.selected_card = Some((PileType::Tableau, 6, 6));
+        same.hints_used = 3;
+        assert!(same.same_position(&game));
+        assert_eq!(same.position_key(), game.position_key());
+        assert_eq!(hash_of(&same.position_key()), hash_of(&game.position_key()));
+
+        // The same cards in the same order, split between the piles differently
+        let mut moved = game.clone();
+        let card = moved.tableau[1].pop().unwrap();
+        moved.tableau[0].push(card);
+        assert_ne!(moved.position_key(), game.position_key());
+
+        let mut moved = game.clone();
+        let card = moved.stock.pop().unwrap();
+        moved.waste.push(card);
+        assert_ne!(moved.position_key(), game.position_key());
+    }
+
+    #[test]
+    fn auto_complete_scores_the_cards_it_uncovers() {
+        let mut game = two_columns("2cAH", "2S");
+        let score = game.score;
+        let earned = game.foundation_move_points() + game.rules.flip_points();
+        assert!(crate::moves::auto_complete_step(&mut game));
+        assert_eq!(game.foundations[0], cards("AH"));
+        assert!(game.tableau[0][0].face_up);
+        assert_eq!(game.score, score + earned, "the uncovered card earns its flip points");
+        assert_eq!(game.undo_stack.last().map(|entry| entry.kind), Some(UndoKind::AutoComplete));
+
+        assert!(game.undo());
+        assert_eq!(game.tableau[0], cards("2cAH"));
+        assert_eq!(game.score, score);
+    }
+}
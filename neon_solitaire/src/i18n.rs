@@ -0,0 +1,216 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    English,
+    Spanish,
+}
+
+impl Language {
+    pub fn strings(&self) -> &'static Strings {
+        match self {
+            Language::English => &ENGLISH,
+            Language::Spanish => &SPANISH,
+        }
+    }
+}
+
+// Every player-facing phrase for one language. Key names in brackets stay the
+// same in every language, since the keys themselves don't change.
+#[derive(Debug)]
+pub struct Strings {
+    // Stats row
+    pub score: &'static str,
+    pub moves: &'static str,
+    pub draw: &'static str,
+    pub one_card: &'static str,    // Padded to the width of three_cards
+    pub three_cards: &'static str,
+    pub all_cards: &'static str,   // The easy draw-all mode
+    pub time: &'static str,
+    pub pass: &'static str,
+    pub hints: &'static str,
+    pub playable: &'static str,    // Legal moves available, in the HUD
+    pub combo: &'static str,       // Foundation move streak, shown as "<combo> x3"
+    pub ceiling: &'static str,     // Highest score still reachable, in the HUD
+
+    // Hint row
+    pub hint: &'static str,
+    pub auto_complete_ready: &'static str,
+
+    // Hints, put together as "<move_card> <card> <from> <to>"
+    pub move_card: &'static str,
+    pub from_column: &'static str,
+    pub from_waste: &'static str,
+    pub from_foundation: &'static str,
+    pub to_column: &'static str,
+    pub to_foundation: &'static str,
+    pub draw_from_stock: &'static str,
+
+    // Status line
+    pub stock_and_waste_empty: &'static str,
+    pub no_passes_left: &'static str,
+    pub turn_over_column: &'static str, // Followed by the column, then "<press> <column>"
+    pub press: &'static str,
+    pub no_legal_moves: &'static str,
+    pub nudge_recycle: &'static str,    // Only the stock can help, and drawing turns the waste over
+    pub nudge_draw: &'static str,       // Only the stock can help
+    pub nothing_to_foundation: &'static str,
+    pub nothing_safe: &'static str,
+    pub no_peeking: &'static str,
+    pub not_finishable: &'static str,
+
+    // Prompts; the recycle question is put together as "<recycle_for> -<penalty> <points_question>"
+    pub recycle_for: &'static str,
+    pub points_question: &'static str,
+    pub final_pass_question: &'static str,
+    pub yes_recycle: &'static str,
+    pub keep_playing: &'static str,
+    pub finish_face_up: &'static str,
+    pub finish_sure_win: &'static str,
+    pub finish_now: &'static str,
+    pub finish_animated: &'static str,
+    pub finish_instant: &'static str,
+    pub finish_myself: &'static str,
+
+    // Controls, below the board; the first line follows "[1-7] "
+    pub controls: &'static str,
+
+    // Welcome screen, below the logo
+    pub welcome: [&'static str; 12],
+}
+
+pub const ENGLISH: Strings = Strings {
+    score: "Score",
+    moves: "Moves",
+    draw: "Draw",
+    one_card: "1 card ",
+    three_cards: "3 cards",
+    all_cards: "all (easy)",
+    time: "Time",
+    pass: "Pass",
+    hints: "Hints",
+    playable: "Playable",
+    combo: "Combo",
+    ceiling: "Max",
+
+    hint: "Hint",
+    auto_complete_ready: "Every card is in reach: press C to finish automatically",
+
+    move_card: "Move",
+    from_column: "from column",
+    from_waste: "from waste",
+    from_foundation: "from foundation",
+    to_column: "to column",
+    to_foundation: "to foundation",
+    draw_from_stock: "Draw from stock",
+
+    stock_and_waste_empty: "The stock and waste are both empty",
+    no_passes_left: "No passes left through the stock",
+    turn_over_column: "Turn over the top card of column",
+    press: "press",
+    no_legal_moves: "No legal moves remain - game over",
+    nudge_recycle: "No moves on the board - draw to turn the waste over",
+    nudge_draw: "No moves on the board - draw from the stock",
+    nothing_to_foundation: "No card can go to a foundation right now",
+    nothing_safe: "No card is safe to send to the foundations yet",
+    no_peeking: "Peeking is off in Vegas and challenge games",
+    not_finishable: "Auto-complete needs the stock used up and every card face up, or a short sure win",
+
+    recycle_for: "Turn the waste back over for",
+    points_question: "points?",
+    final_pass_question: "This is your final pass through the stock - continue?",
+    yes_recycle: "Yes, recycle",
+    keep_playing: "No, keep playing",
+    finish_face_up: "Every card is face up. Finish the game automatically?",
+    finish_sure_win: "Only a short, sure line is left to win. Finish the game automatically?",
+    finish_now: "Send every card to the foundations now?",
+    finish_animated: "Yes, finish the game",
+    finish_instant: "Yes, finish it instantly (no animation)",
+    finish_myself: "No, I'll finish it myself",
+
+    controls: "Columns | [Space] Draw | [Z] Undo | [H] Hint | [?] All keys | [Q] Quit",
+
+    welcome: [
+        "",
+        "              === HOW TO PLAY ===",
+        "",
+        "   • Build foundations from Ace to King by suit",
+        "   • Stack tableau cards in descending order",
+        "   • Alternate colors (red on black, black on red)",
+        "   • Click cards to select, click again to move",
+        "   • Press SPACE to draw cards",
+        "   • Press A for auto-move, P to pause",
+        "",
+        "         Press T for a quick tutorial, or",
+        "              any other key to start...",
+    ],
+};
+
+pub const SPANISH: Strings = Strings {
+    score: "Puntos",
+    moves: "Movs",
+    draw: "Robo",
+    one_card: "1 carta ",
+    three_cards: "3 cartas",
+    all_cards: "todas (fácil)",
+    time: "Tiempo",
+    pass: "Pasada",
+    hints: "Pistas",
+    playable: "Jugables",
+    combo: "Racha",
+    ceiling: "Máx",
+
+    hint: "Pista",
+    auto_complete_ready: "Todas las cartas están a mano: pulsa C para terminar solo",
+
+    move_card: "Mueve",
+    from_column: "de la columna",
+    from_waste: "del descarte",
+    from_foundation: "de la fundación",
+    to_column: "a la columna",
+    to_foundation: "a la fundación",
+    draw_from_stock: "Roba del mazo",
+
+    stock_and_waste_empty: "El mazo y el descarte están vacíos",
+    no_passes_left: "No quedan pasadas por el mazo",
+    turn_over_column: "Da la vuelta a la carta de arriba de la columna",
+    press: "pulsa",
+    no_legal_moves: "No quedan movimientos - fin de la partida",
+    nudge_recycle: "Nada se mueve en la mesa - roba para dar la vuelta al descarte",
+    nudge_draw: "Nada se mueve en la mesa - roba del mazo",
+    nothing_to_foundation: "Ahora ninguna carta puede ir a una fundación",
+    nothing_safe: "Todavía no hay ninguna carta segura para subir a las fundaciones",
+    no_peeking: "No se puede espiar en Vegas ni en los desafíos",
+    not_finishable: "Para terminar solo, el mazo debe estar agotado y todas las cartas boca arriba, o quedar una victoria corta y segura",
+
+    recycle_for: "¿Dar la vuelta al descarte por",
+    points_question: "puntos?",
+    final_pass_question: "Es tu última pasada por el mazo - ¿seguir?",
+    yes_recycle: "Sí, dar la vuelta",
+    keep_playing: "No, seguir jugando",
+    finish_face_up: "Todas las cartas están boca arriba. ¿Terminar la partida solo?",
+    finish_sure_win: "Solo queda una línea corta y segura para ganar. ¿Terminar la partida solo?",
+    finish_now: "¿Subir ya todas las cartas a las fundaciones?",
+    finish_animated: "Sí, terminar la partida",
+    finish_instant: "Sí, terminar al instante (sin animación)",
+    finish_myself: "No, la termino yo",
+
+    controls: "Columnas | [Espacio] Robar | [Z] Deshacer | [H] Pista | [?] Teclas | [Q] Salir",
+
+    welcome: [
+        "",
+        "              === CÓMO JUGAR ===",
+        "",
+        "   • Sube las fundaciones del As al Rey por palo",
+        "   • Apila el tablero en orden descendente",
+        "   • Alterna colores (rojo sobre negro y al revés)",
+        "   • Haz clic en una carta y luego en su destino",
+        "   • Pulsa ESPACIO para robar cartas",
+        "   • Pulsa A para mover solo, P para pausar",
+        "",
+        "       Pulsa T para un tutorial rápido, o",
+        "         cualquier otra tecla para empezar...",
+    ],
+};
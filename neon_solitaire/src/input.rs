@@ -1,42 +1,132 @@
 use crate::card::Card;
-use crate::game::{GameState, PileType};
+use crate::game::{GameState, PileType, PositionKey};
+use crate::layout::Layout;
+use crate::moves::{best_waste_move, cycle_hint, hint_moves, send_to_foundation, Move, MoveLocation};
+use crate::race::PLAYERS;
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind, EnableMouseCapture, DisableMouseCapture},
+    event::{self, Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind, EnableMouseCapture, DisableMouseCapture},
     terminal,
     execute,
 };
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::io::stdout;
 
 #[derive(Copy, Clone, Debug)]
 pub enum InputAction {
     SelectColumn(usize),
+    NextInRun,      // Pick up one card fewer of the selected column's run
     SelectWaste,
     DrawFromStock,
     SelectFoundation(usize),
+    SendToFoundation,
     AutoMove,
+    AutoComplete,
+    SendSafeToFoundations,
+    Peek,
+    Info,
+    ToggleHud,
+    ToggleTeacher,
+    ToggleHistory,
+    QuickStats,
+    KeyReference,
+    ExportPosition,
+    PlayWaste,
+    ToggleDealLoop,
     Undo,
+    UndoDraw,
+    UndoToLastDraw,
+    Redo,
     Hint,
     Quit,
+    QuitNow,       // Ctrl+C: leave without the confirmation prompt
+    Pause,
     ToggleDrawCount,
+    ExportReplay,
+    ToggleMoveList,
+    ScrollMoveList(bool), // true scrolls down
+    MoveCursor(i8, i8),   // Arrow keys: piles across, rows down
+    ActivateCursor,       // Enter: act on the focused pile as its key would
     MouseClick(u16, u16),
+    MouseRightClick(u16, u16),
     MouseDrag(u16, u16),
     None,
 }
 
+// Race mode splits the keyboard: the left player has the number row and Q-T,
+// the right player the bottom letter row and J K L U I.
+// Listed as columns, draw, waste, foundation, undo, auto-move.
+pub const RACE_KEYS: [[&str; 6]; PLAYERS] = [
+    ["1-7", "Q", "W", "E", "R", "T"],
+    ["Z-M", "J", "K", "L", "U", "I"],
+];
+const RIGHT_PLAYER_COLUMNS: &str = "zxcvbnm";
+
+// Every binding handle_key and handle_mouse know, as shown by the ? reference.
+// Keep in step with them when a key changes.
+pub const KEY_REFERENCE: [(&str, &str); 34] = [
+    ("1-9", "Select a column, move to it, or cancel"),
+    ("N", "Pick up one card fewer of the column"),
+    ("W", "Select the waste"),
+    ("Space / S", "Draw from the stock"),
+    ("F", "Send the selection to its foundation"),
+    ("A", "Auto-move one card to a foundation"),
+    ("G", "Send up every safe card"),
+    ("C", "Finish the game automatically"),
+    ("E", "Play the top waste card"),
+    ("H", "Hint"),
+    ("Z", "Undo"),
+    ("Y", "Redo"),
+    ("U", "Undo back to the last draw"),
+    ("D", "Switch between draw 1 and draw 3"),
+    ("L", "Show the legal moves"),
+    ("PgUp / PgDn", "Scroll the legal moves"),
+    ("B", "Switch to the one-line HUD"),
+    ("T", "Teacher mode"),
+    ("O", "Show the undo history"),
+    ("I", "Stock, waste and foundation info"),
+    ("K", "Lifetime statistics"),
+    ("V", "Peek at face-down cards"),
+    ("X", "Save the position to a file"),
+    ("M", "Save the game so far as a replay"),
+    ("R", "Deal until you win"),
+    ("P", "Pause"),
+    ("Arrows", "Move the keyboard cursor"),
+    ("Enter", "Act on the focused pile"),
+    ("?", "This key reference"),
+    ("Q / Esc", "Quit"),
+    ("Ctrl+C", "Quit without asking"),
+    ("Click", "Select a card, or move to a pile"),
+    ("Right-click", "Send a card to its foundation"),
+    ("Wheel", "Draw (down) or undo a draw (up)"),
+];
+
 pub struct InputHandler {
     pub mouse_enabled: bool,
     pub drag_start: Option<(u16, u16)>,
     pub dragging: bool,
 }
 
+impl Default for InputHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl InputHandler {
     pub fn new() -> Self {
+        Self::with_mouse(true)
+    }
+
+    // Without the mouse the terminal keeps its own text selection and copying,
+    // and play is by keyboard alone
+    pub fn with_mouse(mouse_enabled: bool) -> Self {
         let _ = terminal::enable_raw_mode();
-        let _ = execute!(stdout(), EnableMouseCapture);
+        if mouse_enabled {
+            let _ = execute!(stdout(), EnableMouseCapture);
+        }
         
         InputHandler {
-            mouse_enabled: true,
+            mouse_enabled,
             drag_start: None,
             dragging: false,
         }
@@ -51,32 +141,92 @@ impl InputHandler {
         InputAction::None
     }
 
+    // Race mode: which player pressed the key, and what it means on their board.
+    // Quitting belongs to neither player, and the mouse isn't used.
+    pub fn poll_race_input(&mut self) -> (Option<usize>, InputAction) {
+        if event::poll(Duration::from_millis(50)).unwrap_or(false) {
+            if let Ok(Event::Key(key)) = event::read() {
+                return Self::handle_race_key(key);
+            }
+        }
+        (None, InputAction::None)
+    }
+
+    fn handle_race_key(key: KeyEvent) -> (Option<usize>, InputAction) {
+        let c = match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return (None, InputAction::QuitNow),
+            KeyCode::Esc => return (None, InputAction::Quit),
+            KeyCode::Char(c) => c.to_ascii_lowercase(),
+            _ => return (None, InputAction::None),
+        };
+        if let Some(col) = RIGHT_PLAYER_COLUMNS.find(c) {
+            return (Some(1), InputAction::SelectColumn(col));
+        }
+        match c {
+            c @ '1'..='9' => (Some(0), InputAction::SelectColumn(c as usize - '1' as usize)),
+            'q' => (Some(0), InputAction::DrawFromStock),
+            'w' => (Some(0), InputAction::SelectWaste),
+            'e' => (Some(0), InputAction::SendToFoundation),
+            'r' => (Some(0), InputAction::Undo),
+            't' => (Some(0), InputAction::AutoMove),
+            'j' => (Some(1), InputAction::DrawFromStock),
+            'k' => (Some(1), InputAction::SelectWaste),
+            'l' => (Some(1), InputAction::SendToFoundation),
+            'u' => (Some(1), InputAction::Undo),
+            'i' => (Some(1), InputAction::AutoMove),
+            _ => (None, InputAction::None),
+        }
+    }
+
     fn handle_event(&mut self, event: Event) -> InputAction {
         match event {
             Event::Key(key_event) => self.handle_key(key_event),
-            Event::Mouse(mouse_event) => self.handle_mouse(mouse_event),
+            Event::Mouse(mouse_event) if self.mouse_enabled => self.handle_mouse(mouse_event),
             _ => InputAction::None,
         }
     }
 
     fn handle_key(&self, key: KeyEvent) -> InputAction {
         match key.code {
-            KeyCode::Char('1') => InputAction::SelectColumn(0),
-            KeyCode::Char('2') => InputAction::SelectColumn(1),
-            KeyCode::Char('3') => InputAction::SelectColumn(2),
-            KeyCode::Char('4') => InputAction::SelectColumn(3),
-            KeyCode::Char('5') => InputAction::SelectColumn(4),
-            KeyCode::Char('6') => InputAction::SelectColumn(5),
-            KeyCode::Char('7') => InputAction::SelectColumn(6),
+            // Checked before the plain letters so Ctrl+C isn't read as C
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => InputAction::QuitNow,
+            
+            // Columns beyond the current layout are ignored by the game
+            KeyCode::Char(c @ '1'..='9') => InputAction::SelectColumn(c as usize - '1' as usize),
             
             KeyCode::Char('w') | KeyCode::Char('W') => InputAction::SelectWaste,
+            KeyCode::Char('n') | KeyCode::Char('N') => InputAction::NextInRun,
             KeyCode::Char('s') | KeyCode::Char('S') => InputAction::DrawFromStock,
             KeyCode::Char(' ') => InputAction::DrawFromStock,
-            KeyCode::Char('f') | KeyCode::Char('F') => InputAction::AutoMove,
+            KeyCode::Char('f') | KeyCode::Char('F') => InputAction::SendToFoundation,
             KeyCode::Char('a') | KeyCode::Char('A') => InputAction::AutoMove,
+            KeyCode::Char('c') | KeyCode::Char('C') => InputAction::AutoComplete,
+            KeyCode::Char('g') | KeyCode::Char('G') => InputAction::SendSafeToFoundations,
+            KeyCode::Char('v') | KeyCode::Char('V') => InputAction::Peek,
+            KeyCode::Char('i') | KeyCode::Char('I') => InputAction::Info,
+            KeyCode::Char('b') | KeyCode::Char('B') => InputAction::ToggleHud,
+            KeyCode::Char('t') | KeyCode::Char('T') => InputAction::ToggleTeacher,
+            KeyCode::Char('o') | KeyCode::Char('O') => InputAction::ToggleHistory,
+            KeyCode::Char('k') | KeyCode::Char('K') => InputAction::QuickStats,
+            KeyCode::Char('?') => InputAction::KeyReference,
+            KeyCode::Char('x') | KeyCode::Char('X') => InputAction::ExportPosition,
+            KeyCode::Char('e') | KeyCode::Char('E') => InputAction::PlayWaste,
+            KeyCode::Char('r') | KeyCode::Char('R') => InputAction::ToggleDealLoop,
             KeyCode::Char('z') | KeyCode::Char('Z') => InputAction::Undo,
+            KeyCode::Char('u') | KeyCode::Char('U') => InputAction::UndoToLastDraw,
+            KeyCode::Char('y') | KeyCode::Char('Y') => InputAction::Redo,
             KeyCode::Char('h') | KeyCode::Char('H') => InputAction::Hint,
             KeyCode::Char('d') | KeyCode::Char('D') => InputAction::ToggleDrawCount,
+            KeyCode::Char('p') | KeyCode::Char('P') => InputAction::Pause,
+            KeyCode::Char('l') | KeyCode::Char('L') => InputAction::ToggleMoveList,
+            KeyCode::Char('m') | KeyCode::Char('M') => InputAction::ExportReplay,
+            KeyCode::PageUp => InputAction::ScrollMoveList(false),
+            KeyCode::PageDown => InputAction::ScrollMoveList(true),
+            KeyCode::Left => InputAction::MoveCursor(-1, 0),
+            KeyCode::Right => InputAction::MoveCursor(1, 0),
+            KeyCode::Up => InputAction::MoveCursor(0, -1),
+            KeyCode::Down => InputAction::MoveCursor(0, 1),
+            KeyCode::Enter => InputAction::ActivateCursor,
             KeyCode::Char('q') | KeyCode::Char('Q') => InputAction::Quit,
             KeyCode::Esc => InputAction::Quit,
             
@@ -89,6 +239,11 @@ impl InputHandler {
             MouseEventKind::Down(MouseButton::Left) => {
                 InputAction::MouseClick(mouse.column, mouse.row)
             }
+            MouseEventKind::Down(MouseButton::Right) => {
+                InputAction::MouseRightClick(mouse.column, mouse.row)
+            }
+            MouseEventKind::ScrollDown => InputAction::DrawFromStock,
+            MouseEventKind::ScrollUp => InputAction::UndoDraw,
             _ => InputAction::None,
         }
     }
@@ -99,190 +254,262 @@ impl InputHandler {
     }
 }
 
-pub fn convert_mouse_to_game_position(x: u16, y: u16, game: &GameState) -> Option<(PileType, usize, usize)> {
-    // Stock area
-    if y == 6 && x >= 9 && x <= 14 {
+pub fn convert_mouse_to_game_position(x: u16, y: u16, game: &GameState, layout: &Layout) -> Option<(PileType, usize, usize)> {
+    let x = x.checked_sub(layout.left)?;
+    
+    // A row either side of the piles still counts, so a slightly-off click keeps the selection
+    let pile_row = layout.y(Layout::PILE_ROW);
+    let on_pile_row = y.abs_diff(pile_row) <= 1;
+    
+    // Stock area, including its label
+    if on_pile_row && (2..=Layout::STOCK_LEFT + 6).contains(&x) {
         return Some((PileType::Stock, 0, 0));
     }
     
     // Waste area
-    if y == 6 && x >= 16 && x <= 35 {
-        if !game.waste.is_empty() {
-            return Some((PileType::Waste, 0, game.waste.len() - 1));
-        }
+    if on_pile_row && (16..=35).contains(&x) && !game.waste.is_empty() {
+        return Some((PileType::Waste, 0, game.waste.len() - 1));
     }
     
-    // Foundation area
-    if y == 6 && x >= 53 && x <= 70 {
-        let foundation_idx = ((x - 53) / 5) as usize;
-        if foundation_idx < 4 {
-            return Some((PileType::Foundation, foundation_idx, 0));
-        }
+    // Foundation area, with a little slack before the first and after the last
+    let foundations_right = Layout::FOUNDATION_LEFT + game.foundations.len() as u16 * Layout::FOUNDATION_WIDTH;
+    if on_pile_row && x + Layout::CLICK_SLACK >= Layout::FOUNDATION_LEFT && x < foundations_right + Layout::CLICK_SLACK {
+        let foundation_idx = (x.saturating_sub(Layout::FOUNDATION_LEFT) / Layout::FOUNDATION_WIDTH) as usize;
+        return Some((PileType::Foundation, foundation_idx.min(game.foundations.len() - 1), 0));
     }
     
-    // Tableau area - FIXED: properly handle clicking on columns
-    if y >= 10 && x >= 2 && x <= 44 {
-        let col = ((x - 2) / 6) as usize;
-        if col < 7 {
-            let row = (y - 10) as usize;
-            // If clicking on an empty column or beyond the cards, return the column with row 0
-            if game.tableau[col].is_empty() || row >= game.tableau[col].len() {
-                return Some((PileType::Tableau, col, game.tableau[col].len()));
-            } else {
-                return Some((PileType::Tableau, col, row));
-            }
-        }
+    // Tableau area, using the same column spacing the board is drawn with
+    let scroll = layout.tableau_scroll(game);
+    if let (Some(col), Some(row)) = (layout.column_at(x, game.rules.tableau_columns), layout.row_at(y, scroll)) {
+        let column = &game.tableau[col];
+        // An empty column is a drop target only
+        let Some(last) = column.len().checked_sub(1) else {
+            return Some((PileType::Tableau, col, 0));
+        };
+        // Below the cards means the bottom card, and a face-down card means the first face-up one
+        let row = row.min(last);
+        let row = match game.first_face_up(col) {
+            Some(first) if first > row => first,
+            _ => row,
+        };
+        return Some((PileType::Tableau, col, row));
     }
     
     None
 }
 
-pub fn handle_game_action(game: &mut GameState, action: InputAction) -> bool {
+// What an action came to, so the caller can quit or redraw without comparing
+// the game before and after itself
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ActionOutcome {
+    Unchanged, // Nothing on screen is any different
+    Changed,   // The board, selection, cursor, hint or status line changed
+    Won,       // The action finished the game
+    Quit,      // The player asked to leave
+}
+
+// Everything an action can change that the board shows
+#[derive(PartialEq)]
+struct Shown {
+    position: PositionKey,
+    selected: Option<(PileType, usize, usize)>,
+    cursor: Option<(PileType, usize)>,
+    hint: Option<(MoveLocation, MoveLocation)>,
+    score: i32,
+    moves: u32,
+    draw_count: usize,
+    status_at: Option<Instant>,
+    history: (usize, usize),
+}
+
+impl Shown {
+    fn of(game: &GameState) -> Self {
+        Shown {
+            position: game.position_key(),
+            selected: game.selected_card,
+            cursor: game.cursor,
+            hint: game.hint.as_ref().map(|hint| (hint.from.clone(), hint.to.clone())),
+            score: game.score,
+            moves: game.move_count,
+            draw_count: game.draw_count,
+            status_at: game.status_message.as_ref().map(|(_, shown)| *shown),
+            history: (game.undo_stack.len(), game.redo_stack.len()),
+        }
+    }
+}
+
+pub fn handle_game_action(game: &mut GameState, action: InputAction, layout: &Layout) -> ActionOutcome {
+    if matches!(action, InputAction::None) {
+        return ActionOutcome::Unchanged;
+    }
+    let before = Shown::of(game);
+    let was_won = game.is_won();
+    if apply_action(game, action, layout) {
+        ActionOutcome::Quit
+    } else if game.is_won() && !was_won {
+        ActionOutcome::Won
+    } else if Shown::of(game) != before {
+        ActionOutcome::Changed
+    } else {
+        ActionOutcome::Unchanged
+    }
+}
+
+// What Enter does on the focused pile: the same as that pile's own key
+pub fn cursor_action(game: &GameState) -> InputAction {
+    match game.cursor {
+        Some((PileType::Stock, _)) => InputAction::DrawFromStock,
+        Some((PileType::Waste, _)) => InputAction::SelectWaste,
+        Some((PileType::Foundation, f)) => InputAction::SelectFoundation(f),
+        Some((PileType::Tableau, col)) => InputAction::SelectColumn(col),
+        None => InputAction::None,
+    }
+}
+
+// Carries out an action; returns true if it was a request to quit
+fn apply_action(game: &mut GameState, action: InputAction, layout: &Layout) -> bool {
+    // A highlighted hint lasts until the player does something else
+    if !matches!(action, InputAction::Hint | InputAction::None) {
+        game.clear_hint();
+    }
+    
     match action {
-        InputAction::SelectColumn(col) => {
-            if col < 7 {
-                if let Some((pile_type, from_col, from_row)) = game.selected_card {
-                    // We have a selected card, try to move it to this column
-                    match pile_type {
-                        PileType::Tableau => {
-                            if from_col != col {
-                                let cards_to_move: Vec<Card> = game.tableau[from_col]
-                                    .drain(from_row..)
-                                    .collect();
-                                
-                                if !cards_to_move.is_empty() && 
-                                   game.is_valid_tableau_move(&cards_to_move[0], col) {
-                                    game.save_undo_state();
-                                    for card in cards_to_move {
-                                        game.tableau[col].push(card);
-                                    }
-                                    
-                                    if let Some(new_top) = game.tableau[from_col].last_mut() {
-                                        if !new_top.face_up {
-                                            new_top.face_up = true;
-                                            game.score += 5;
-                                        }
-                                    }
-                                    
-                                    game.move_count += 1;
-                                    game.score += 5;
-                                } else {
-                                    for card in cards_to_move {
-                                        game.tableau[from_col].push(card);
-                                    }
-                                }
-                            }
-                        }
-                        PileType::Waste => {
-                            if let Some(&card) = game.waste.last() {
-                                if game.is_valid_tableau_move(&card, col) {
-                                    game.save_undo_state();
-                                    let card = game.waste.pop().unwrap();
-                                    game.tableau[col].push(card);
-                                    game.move_count += 1;
-                                    game.score += 5;
-                                }
-                            }
+        // Slides the anchor of a tableau selection one card down its run, so only
+        // the cards below move, and from the bottom card back to the top of the run
+        InputAction::NextInRun => match game.selected_card {
+            Some((PileType::Tableau, col, from_row)) => {
+                let next = if from_row + 1 < game.tableau[col].len() {
+                    from_row + 1
+                } else {
+                    game.first_face_up(col).unwrap_or(from_row)
+                };
+                game.selected_card = Some((PileType::Tableau, col, next));
+                if let Some(summary) = game.describe_selection() {
+                    game.show_status(summary);
+                }
+            }
+            _ => game.show_status("Select a column first, then press N to pick up fewer of its cards".to_string()),
+        },
+        // Pressing the selected column again lets go of it, as W does for the waste
+        InputAction::SelectColumn(col)
+            if matches!(game.selected_card, Some((PileType::Tableau, c, _)) if c == col) =>
+        {
+            game.selected_card = None;
+        }
+        InputAction::SelectColumn(col) if col < game.rules.tableau_columns => {
+            if let Some((pile_type, from_col, from_row)) = game.selected_card {
+                // We have a selected card, try to move it to this column
+                match pile_type {
+                    PileType::Tableau if from_col != col => {
+                        try_move(game, tableau_location(from_col, from_row), tableau_target(game, col));
+                    }
+                    PileType::Waste => {
+                        if let Some(from) = waste_location(game) {
+                            try_move(game, from, tableau_target(game, col));
                         }
-                        _ => {}
                     }
-                    game.selected_card = None;
-                } else {
-                    // No card selected, select one from this column
-                    if !game.tableau[col].is_empty() {
-                        // Find the first face-up card
-                        for i in 0..game.tableau[col].len() {
-                            if game.tableau[col][i].face_up {
-                                game.selected_card = Some((PileType::Tableau, col, i));
-                                break;
-                            }
+                    PileType::Foundation => {
+                        if let Some(from) = foundation_location(game, from_col) {
+                            try_move(game, from, tableau_target(game, col));
                         }
                     }
+                    _ => {}
+                }
+                game.selected_card = None;
+            } else if game.flip_top(col) {
+                // Without auto-flip, choosing a column turns its face-down top card over
+            } else {
+                // No card selected, select the whole face-up run of this column
+                if let Some(i) = game.first_face_up(col) {
+                    game.selected_card = Some((PileType::Tableau, col, i));
+                    if let Some(summary) = game.describe_selection() {
+                        game.show_status(summary);
+                    }
                 }
             }
         }
-        InputAction::SelectWaste => {
-            if !game.waste.is_empty() {
-                if game.selected_card == Some((PileType::Waste, 0, game.waste.len() - 1)) {
-                    game.selected_card = None;
-                } else {
-                    game.selected_card = Some((PileType::Waste, 0, game.waste.len() - 1));
+        InputAction::SelectWaste if !game.waste.is_empty() => {
+            if game.selected_card == Some((PileType::Waste, 0, game.waste.len() - 1)) {
+                game.selected_card = None;
+            } else {
+                game.selected_card = Some((PileType::Waste, 0, game.waste.len() - 1));
+            }
+        }
+        // The top waste card straight to its best spot, without selecting anything
+        InputAction::PlayWaste => match (game.waste.last().copied(), best_waste_move(game)) {
+            (None, _) => game.show_status("The waste is empty".to_string()),
+            (Some(card), None) => game.reject_move(format!("{} has nowhere to go", card)),
+            (Some(_), Some(mut mv)) => {
+                mv.execute(game);
+                game.selected_card = None;
+            }
+        },
+        InputAction::SendToFoundation => {
+            // Just the selected pile's top card, or the waste's with nothing selected;
+            // unlike A, nothing else is swept up and no tableau move is tried
+            let source = match game.selected_card {
+                Some((PileType::Tableau, col, _)) => Some((PileType::Tableau, col, game.tableau[col].last().copied())),
+                Some((PileType::Waste, _, _)) | None => Some((PileType::Waste, 0, game.waste.last().copied())),
+                Some(_) => None,
+            };
+            match source {
+                None => game.show_status("Select a column or the waste, then press F".to_string()),
+                Some((PileType::Waste, _, None)) => game.show_status("The waste is empty".to_string()),
+                Some((_, col, None)) => game.show_status(format!("Column {} is empty", col + 1)),
+                Some((from_pile, pile_index, Some(card))) => {
+                    if !send_to_foundation(game, from_pile, pile_index) {
+                        game.reject_move(format!("{} can't go to a foundation yet", card));
+                    }
                 }
             }
+            game.selected_card = None;
         }
         InputAction::DrawFromStock => {
             game.draw_from_stock();
             game.selected_card = None;
         }
         InputAction::AutoMove => {
-            // Try auto-move to foundation first
-            if !game.auto_move_to_foundation() {
-                // If no foundation moves, try the hint move
-                if let Some(hint) = game.get_hint() {
-                    // Parse hint to execute it
-                    if hint.contains("from column") && hint.contains("to column") {
-                        // Extract column numbers from hint
-                        let parts: Vec<&str> = hint.split_whitespace().collect();
-                        if let Some(from_pos) = parts.iter().position(|&x| x == "column") {
-                            if let Some(to_pos) = parts.iter().rposition(|&x| x == "column") {
-                                if from_pos < parts.len() - 1 && to_pos < parts.len() - 1 {
-                                    if let (Ok(from_col), Ok(to_col)) = (
-                                        parts[from_pos + 1].parse::<usize>(),
-                                        parts[to_pos + 1].parse::<usize>()
-                                    ) {
-                                        // Execute the hinted move
-                                        let from_col = from_col - 1;  // Convert to 0-based
-                                        let to_col = to_col - 1;
-                                        
-                                        if from_col < 7 && to_col < 7 && !game.tableau[from_col].is_empty() {
-                                            // Find first face-up card
-                                            for i in 0..game.tableau[from_col].len() {
-                                                if game.tableau[from_col][i].face_up {
-                                                    let cards_to_move: Vec<Card> = game.tableau[from_col]
-                                                        .drain(i..)
-                                                        .collect();
-                                                    
-                                                    if !cards_to_move.is_empty() && 
-                                                       game.is_valid_tableau_move(&cards_to_move[0], to_col) {
-                                                        game.save_undo_state();
-                                                        for card in cards_to_move {
-                                                            game.tableau[to_col].push(card);
-                                                        }
-                                                        
-                                                        if let Some(new_top) = game.tableau[from_col].last_mut() {
-                                                            if !new_top.face_up {
-                                                                new_top.face_up = true;
-                                                                game.score += 5;
-                                                            }
-                                                        }
-                                                        
-                                                        game.move_count += 1;
-                                                        game.score += 5;
-                                                    } else {
-                                                        for card in cards_to_move {
-                                                            game.tableau[from_col].push(card);
-                                                        }
-                                                    }
-                                                    break;
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
+            // Foundation moves first, otherwise the best tableau-to-tableau suggestion
+            let moved = game.auto_move_to_foundation().is_some();
+            if !moved {
+                let shuffle = hint_moves(game).into_iter().find(|mv| {
+                    mv.from.pile_type == PileType::Tableau && mv.to.pile_type == PileType::Tableau
+                });
+                if let Some(mut mv) = shuffle {
+                    mv.execute(game);
                 }
             }
         }
         InputAction::Undo => {
+            if let (false, Some(reason)) = (game.undo(), game.undo_off_reason()) {
+                game.show_status(reason.to_string());
+            }
+        }
+        InputAction::Redo => {
+            game.redo();
+        }
+        InputAction::Hint => {
+            cycle_hint(game);
+        }
+        InputAction::UndoToLastDraw => {
+            let undone = game.undo_to_last_draw();
+            if undone {
+                game.selected_card = None;
+            }
+        }
+        // Scrolling back only rewinds a draw, never a card move
+        InputAction::UndoDraw if game.last_action_was_draw() => {
             game.undo();
+            game.selected_card = None;
+        }
+        InputAction::ToggleDrawCount if game.rules.draw_all => {
+            game.show_status("Easy mode draws the whole stock; start without --draw all for 1 or 3".to_string());
         }
         InputAction::ToggleDrawCount => {
             game.draw_count = if game.draw_count == 1 { 3 } else { 1 };
         }
         InputAction::MouseClick(x, y) => {
-            if let Some(position) = convert_mouse_to_game_position(x, y, game) {
+            if let Some(position) = convert_mouse_to_game_position(x, y, game, layout) {
                 match position.0 {
                     PileType::Stock => {
                         game.draw_from_stock();
@@ -302,52 +529,24 @@ pub fn handle_game_action(game: &mut GameState, action: InputAction) -> bool {
                             // We have a selected card, try to move it here
                             match from_pile {
                                 PileType::Waste => {
-                                    if let Some(&card) = game.waste.last() {
-                                        if game.is_valid_tableau_move(&card, col) {
-                                            game.save_undo_state();
-                                            game.waste.pop();
-                                            game.tableau[col].push(card);
-                                            game.move_count += 1;
-                                            game.score += 5;
-                                        }
+                                    if let Some(from) = waste_location(game) {
+                                        try_move(game, from, tableau_target(game, col));
                                     }
                                 }
                                 PileType::Tableau if from_col != col => {
-                                    // Move from one tableau column to another
-                                    let cards_to_move: Vec<Card> = game.tableau[from_col]
-                                        .drain(from_row..)
-                                        .collect();
-                                    
-                                    if !cards_to_move.is_empty() && 
-                                       game.is_valid_tableau_move(&cards_to_move[0], col) {
-                                        game.save_undo_state();
-                                        for card in cards_to_move {
-                                            game.tableau[col].push(card);
-                                        }
-                                        
-                                        if let Some(new_top) = game.tableau[from_col].last_mut() {
-                                            if !new_top.face_up {
-                                                new_top.face_up = true;
-                                                game.score += 5;
-                                            }
-                                        }
-                                        
-                                        game.move_count += 1;
-                                        game.score += 5;
-                                    } else {
-                                        // Invalid move, put cards back
-                                        for card in cards_to_move {
-                                            game.tableau[from_col].push(card);
-                                        }
-                                    }
+                                    try_move(game, tableau_location(from_col, from_row), tableau_target(game, col));
                                 }
-                                PileType::Tableau if from_col == col => {
-                                    // Clicking on same column, just deselect
-                                    game.selected_card = None;
+                                PileType::Foundation => {
+                                    if let Some(from) = foundation_location(game, from_col) {
+                                        try_move(game, from, tableau_target(game, col));
+                                    }
                                 }
+                                // Clicking the same column again just deselects
                                 _ => {}
                             }
                             game.selected_card = None;
+                        } else if clicked_row + 1 == game.tableau[col].len() && game.flip_top(col) {
+                            // Clicking a face-down top card turns it over
                         } else {
                             // No card selected, select one if clicking on a face-up card
                             if clicked_row < game.tableau[col].len() && game.tableau[col][clicked_row].face_up {
@@ -361,56 +560,304 @@ pub fn handle_game_action(game: &mut GameState, action: InputAction) -> bool {
                             }
                         }
                     }
-                    PileType::Foundation => {
-                        let f_idx = position.1;
-                        if let Some((from_pile, from_col, _)) = game.selected_card {
-                            match from_pile {
-                                PileType::Waste => {
-                                    if let Some(&card) = game.waste.last() {
-                                        if game.is_valid_foundation_move(&card, f_idx) {
-                                            game.save_undo_state();
-                                            let card = game.waste.pop().unwrap();
-                                            game.foundations[f_idx].push(card);
-                                            game.score += 10;
-                                            game.move_count += 1;
-                                        }
-                                    }
-                                }
-                                PileType::Tableau => {
-                                    if let Some(&card) = game.tableau[from_col].last() {
-                                        if game.is_valid_foundation_move(&card, f_idx) {
-                                            game.save_undo_state();
-                                            let card = game.tableau[from_col].pop().unwrap();
-                                            game.foundations[f_idx].push(card);
-                                            
-                                            if let Some(new_top) = game.tableau[from_col].last_mut() {
-                                                if !new_top.face_up {
-                                                    new_top.face_up = true;
-                                                    game.score += 5;
-                                                }
-                                            }
-                                            
-                                            game.score += 10;
-                                            game.move_count += 1;
-                                        }
-                                    }
-                                }
-                                _ => {}
+                    // With nothing selected, a foundation's top card is picked up so it
+                    // can be brought back down onto a column
+                    PileType::Foundation => match game.foundations[position.1].len() {
+                        top @ 1.. if game.selected_card.is_none() => {
+                            if game.rules.allow_foundation_to_tableau {
+                                game.selected_card = Some((PileType::Foundation, position.1, top - 1));
+                            } else {
+                                game.show_status("Strict rules: cards stay on the foundations".to_string());
                             }
-                            game.selected_card = None;
                         }
-                    }
+                        _ => move_selection_to_foundation(game, position.1),
+                    },
                 }
             } else {
                 // Clicked outside, deselect
                 game.selected_card = None;
             }
         }
-        InputAction::Quit => {
+        InputAction::SelectFoundation(f_idx) if f_idx < game.foundations.len() => {
+            move_selection_to_foundation(game, f_idx);
+        }
+        InputAction::MoveCursor(across, down) => {
+            game.cursor = Some(match game.cursor {
+                Some(pile) => step_cursor(game, layout, pile, across, down),
+                None => (PileType::Stock, 0), // The first press just shows where focus starts
+            });
+        }
+        InputAction::ActivateCursor => {
+            return apply_action(game, cursor_action(game), layout);
+        }
+        InputAction::MouseRightClick(x, y) => {
+            // Right-click sends the clicked card straight to a foundation, if it can go
+            let sent = match convert_mouse_to_game_position(x, y, game, layout) {
+                Some((PileType::Waste, _, _)) => send_to_foundation(game, PileType::Waste, 0),
+                Some((PileType::Tableau, col, row)) if row + 1 == game.tableau[col].len() => {
+                    send_to_foundation(game, PileType::Tableau, col)
+                }
+                _ => false,
+            };
+            if sent {
+                game.selected_card = None;
+            }
+        }
+        InputAction::Quit | InputAction::QuitNow => {
             return true;
         }
         _ => {}
     }
     
     false
-}
\ No newline at end of file
+}
+
+// Plays the selection onto one particular foundation, as clicking it does
+fn move_selection_to_foundation(game: &mut GameState, f_idx: usize) {
+    if let Some((from_pile, from_col, _)) = game.selected_card {
+        // Only the top card of a column can go up, whatever run is selected
+        let from = match from_pile {
+            PileType::Waste => waste_location(game),
+            PileType::Tableau => game.tableau[from_col]
+                .len()
+                .checked_sub(1)
+                .map(|top| tableau_location(from_col, top)),
+            _ => None,
+        };
+        if let Some(from) = from {
+            let to = MoveLocation {
+                pile_type: PileType::Foundation,
+                pile_index: f_idx,
+                card_index: game.foundations[f_idx].len(),
+            };
+            try_move(game, from, to);
+        }
+        game.selected_card = None;
+    }
+}
+
+// The keyboard cursor moves along a row of piles, stopping at either end. Up and
+// down switch between the stock, waste and foundations and the tableau below,
+// landing on whichever pile starts nearest the one left.
+fn step_cursor(game: &GameState, layout: &Layout, (pile_type, index): (PileType, usize), across: i8, down: i8) -> (PileType, usize) {
+    let top_row: Vec<(PileType, usize)> = [(PileType::Stock, 0), (PileType::Waste, 0)]
+        .into_iter()
+        .chain((0..game.foundations.len()).map(|f| (PileType::Foundation, f)))
+        .collect();
+    let tableau_row: Vec<(PileType, usize)> = (0..game.rules.tableau_columns).map(|col| (PileType::Tableau, col)).collect();
+    let on_top = pile_type != PileType::Tableau;
+    let row = if on_top { &top_row } else { &tableau_row };
+    let position = row.iter().position(|&pile| pile == (pile_type, index)).unwrap_or(0);
+    
+    let switch_to = match down {
+        1 if on_top => Some(&tableau_row),
+        -1 if !on_top => Some(&top_row),
+        _ => None,
+    };
+    match switch_to {
+        Some(other) => {
+            let x = layout.pile_x(pile_type, index);
+            *other.iter().min_by_key(|&&(p, i)| layout.pile_x(p, i).abs_diff(x)).unwrap_or(&row[position])
+        }
+        None => row[position.saturating_add_signed(across as isize).min(row.len() - 1)],
+    }
+}
+
+fn waste_location(game: &GameState) -> Option<MoveLocation> {
+    game.waste.len().checked_sub(1).map(|top| MoveLocation {
+        pile_type: PileType::Waste,
+        pile_index: 0,
+        card_index: top,
+    })
+}
+
+fn foundation_location(game: &GameState, f: usize) -> Option<MoveLocation> {
+    game.foundations[f].len().checked_sub(1).map(|top| MoveLocation {
+        pile_type: PileType::Foundation,
+        pile_index: f,
+        card_index: top,
+    })
+}
+
+fn tableau_location(col: usize, row: usize) -> MoveLocation {
+    MoveLocation {
+        pile_type: PileType::Tableau,
+        pile_index: col,
+        card_index: row,
+    }
+}
+
+fn tableau_target(game: &GameState, col: usize) -> MoveLocation {
+    tableau_location(col, game.tableau[col].len())
+}
+
+// Every player move goes through Move::execute, so the undo snapshot is taken
+// before anything changes. Refusals are explained on the status line.
+fn try_move(game: &mut GameState, from: MoveLocation, to: MoveLocation) -> bool {
+    let cards: Vec<Card> = match from.pile_type {
+        PileType::Tableau => game.tableau[from.pile_index]
+            .get(from.card_index..)
+            .map_or_else(Vec::new, <[Card]>::to_vec),
+        PileType::Waste => game.waste.last().copied().into_iter().collect(),
+        PileType::Foundation => game.foundations[from.pile_index].last().copied().into_iter().collect(),
+        _ => Vec::new(),
+    };
+    let Some(&card) = cards.first() else {
+        return false;
+    };
+
+    let reason = match to.pile_type {
+        PileType::Foundation => game.explain_foundation_move(&card, to.pile_index),
+        _ => game.explain_tableau_move(&card, to.pile_index),
+    };
+    let mut mv = Move::new(from, to, cards);
+    // Putting cards back where they were is just letting go of them, not an illegal move
+    if mv.is_no_op() {
+        return false;
+    }
+    if mv.execute(game) {
+        true
+    } else {
+        game.reject_move(reason);
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn column_key_toggles_its_selection() {
+        let mut game = GameState::new_with_seed(5);
+        let layout = Layout::default();
+        handle_game_action(&mut game, InputAction::SelectColumn(6), &layout);
+        assert_eq!(game.selected_card, Some((PileType::Tableau, 6, 6)));
+        handle_game_action(&mut game, InputAction::SelectColumn(6), &layout);
+        assert_eq!(game.selected_card, None, "a second press of the same column cancels");
+        handle_game_action(&mut game, InputAction::SelectColumn(6), &layout);
+        assert_eq!(game.selected_card, Some((PileType::Tableau, 6, 6)), "and a third selects it again");
+    }
+
+    // A King in column 1 and a Queen in column 2, with every other column empty
+    fn king_and_queen() -> GameState {
+        let mut tableau = vec![Vec::new(); 7];
+        tableau[0] = vec![Card::from_code("KS").unwrap()];
+        tableau[1] = vec![Card::from_code("QH").unwrap()];
+        GameState::with_fixed_tableau(tableau, crate::rules::RulesConfig::default(), 1).unwrap()
+    }
+
+    #[test]
+    fn column_key_moves_a_king_to_an_empty_column() {
+        let mut game = king_and_queen();
+        let layout = Layout::default();
+        handle_game_action(&mut game, InputAction::SelectColumn(0), &layout);
+        handle_game_action(&mut game, InputAction::SelectColumn(3), &layout);
+        assert!(game.tableau[0].is_empty());
+        assert_eq!(game.tableau[3], vec![Card::from_code("KS").unwrap()]);
+        assert_eq!(game.selected_card, None);
+    }
+
+    #[test]
+    fn column_key_refuses_anything_else_on_an_empty_column() {
+        let mut game = king_and_queen();
+        let layout = Layout::default();
+        handle_game_action(&mut game, InputAction::SelectColumn(1), &layout);
+        handle_game_action(&mut game, InputAction::SelectColumn(3), &layout);
+        assert!(game.tableau[3].is_empty());
+        assert!(game.move_rejected);
+        let status = game.status_message.as_ref().map(|(text, _)| text.as_str());
+        assert_eq!(status, Some("Only a King can move to an empty column"));
+    }
+
+    #[test]
+    fn clicks_map_to_the_card_drawn_there() {
+        let mut game = GameState::new_with_seed(5);
+        let layout = Layout::default();
+        let x = layout.column_x(6);
+        assert_eq!(convert_mouse_to_game_position(x, Layout::TABLEAU_HEADER_ROW, &game, &layout), Some((PileType::Tableau, 6, 6)));
+        assert_eq!(convert_mouse_to_game_position(x, Layout::TABLEAU_TOP, &game, &layout), Some((PileType::Tableau, 6, 6)), "a face-down card picks up the run");
+        assert_eq!(convert_mouse_to_game_position(layout.column_x(1), Layout::TABLEAU_TOP + 1, &game, &layout), Some((PileType::Tableau, 1, 1)));
+        assert_eq!(convert_mouse_to_game_position(layout.column_x(1), Layout::TABLEAU_TOP + 8, &game, &layout), Some((PileType::Tableau, 1, 1)), "below a short column is its bottom card");
+
+        // Twelve more cards make column 1 taller than the tableau, and selecting near its bottom scrolls
+        let extra: Vec<Card> = game.stock.drain(..12).map(|card| Card { face_up: true, ..card }).collect();
+        game.tableau[0].extend(extra);
+        game.selected_card = Some((PileType::Tableau, 0, 12));
+        assert_eq!(layout.tableau_scroll(&game), 1);
+        assert_eq!(convert_mouse_to_game_position(layout.column_x(0), Layout::TABLEAU_TOP, &game, &layout), Some((PileType::Tableau, 0, 1)));
+        let last_row = Layout::TABLEAU_TOP + Layout::TABLEAU_ROWS as u16 - 1;
+        assert_eq!(convert_mouse_to_game_position(layout.column_x(0), last_row, &game, &layout), Some((PileType::Tableau, 0, 12)));
+    }
+
+    // A bot reading the board through the query views and playing through
+    // handle_game_action: foundation moves when it sees one, otherwise a draw
+    #[test]
+    fn bot_plays_a_pass_through_the_stock() {
+        let mut game = GameState::new_with_seed(5);
+        let layout = Layout::default();
+        let columns = game.rules.tableau_columns;
+        assert_eq!(game.foundation_tops().count(), 4);
+        assert!(game.foundation_tops().all(|top| top.is_none()));
+        assert_eq!(game.playable_waste_card(), None);
+        assert_eq!(game.face_up_run(6).len(), 1);
+
+        loop {
+            let fits = |card: &Card| (0..game.foundations.len()).any(|f| game.is_valid_foundation_move(card, f));
+            let sent = if let Some(card) = game.playable_waste_card().filter(fits) {
+                assert_eq!(handle_game_action(&mut game, InputAction::SendToFoundation, &layout), ActionOutcome::Changed);
+                card
+            } else if let Some(col) = (0..columns).find(|&col| game.face_up_run(col).last().is_some_and(fits)) {
+                let card = *game.face_up_run(col).last().unwrap();
+                handle_game_action(&mut game, InputAction::SelectColumn(col), &layout);
+                assert_eq!(handle_game_action(&mut game, InputAction::SendToFoundation, &layout), ActionOutcome::Changed);
+                assert!(!game.face_up_run(col).is_empty() || game.tableau[col].is_empty(), "the card under it is turned over");
+                card
+            } else if game.stock_count() > 0 {
+                let left = game.stock_count();
+                assert_eq!(handle_game_action(&mut game, InputAction::DrawFromStock, &layout), ActionOutcome::Changed);
+                assert_eq!(game.stock_count(), left.saturating_sub(game.draw_count));
+                assert!(game.playable_waste_card().is_some());
+                continue;
+            } else {
+                break;
+            };
+            assert!(game.foundation_tops().any(|top| top == Some(sent)), "{} went up", sent);
+        }
+        assert!(game.foundation_tops().flatten().count() > 0, "the bot found something to send up");
+    }
+
+    #[test]
+    fn n_picks_up_one_card_fewer_and_wraps() {
+        let mut tableau = vec![Vec::new(); 7];
+        tableau[0] = ["kd", "9S", "8H", "7S"].iter().map(|code| Card::from_code(code).unwrap()).collect();
+        let mut game = GameState::with_fixed_tableau(tableau, crate::rules::RulesConfig::default(), 1).unwrap();
+        let layout = Layout::default();
+
+        handle_game_action(&mut game, InputAction::NextInRun, &layout);
+        assert_eq!(game.selected_card, None, "nothing to shorten without a selection");
+        handle_game_action(&mut game, InputAction::SelectColumn(0), &layout);
+        assert_eq!(game.selected_card, Some((PileType::Tableau, 0, 1)));
+        for anchor in [2, 3, 1] {
+            handle_game_action(&mut game, InputAction::NextInRun, &layout);
+            assert_eq!(game.selected_card, Some((PileType::Tableau, 0, anchor)), "from the bottom card back to the whole run");
+        }
+    }
+
+    #[test]
+    fn moving_cards_onto_their_own_spot_changes_nothing() {
+        let mut game = GameState::new_with_seed(5);
+        let (score, moves) = (game.score, game.move_count);
+        let cards = game.tableau[6][6..].to_vec();
+
+        let mut mv = Move::new(tableau_location(6, 6), tableau_location(6, 6), cards.clone());
+        assert!(!mv.execute(&mut game));
+        assert!(!try_move(&mut game, tableau_location(6, 6), tableau_location(6, 6)));
+
+        assert_eq!(game.tableau[6][6..], cards[..]);
+        assert_eq!(game.score, score);
+        assert_eq!(game.move_count, moves);
+        assert!(game.undo_stack.is_empty(), "nothing to undo");
+        assert!(!game.move_rejected, "letting go of cards is not an illegal move");
+    }
+}
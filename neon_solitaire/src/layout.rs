@@ -0,0 +1,213 @@
+use crate::game::{GameState, PileType};
+use serde::{Deserialize, Serialize};
+
+// How far apart tableau columns are drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Spacing {
+    #[default]
+    Compact,  // Fits an 80-column terminal
+    Spacious, // Wider gaps for wide terminals
+}
+
+// Screen positions shared by drawing and mouse hit-testing, so the two can't drift apart
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layout {
+    pub column_width: u16,
+    pub left: u16, // Screen column the board starts at; non-zero for the second board in a race
+    pub hud: bool, // One-line status bar in place of the title and stats, moving the board up
+    pub height: u16, // Terminal rows; u16::MAX until measured, which leaves everything at full size
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self::new(Spacing::Compact)
+    }
+}
+
+impl Layout {
+    pub const STATS_ROW: u16 = 4;         // Score, moves and the rest, without the HUD
+    pub const PILE_ROW: u16 = 6;          // Stock, waste and foundations
+    pub const STOCK_LEFT: u16 = 9;
+    pub const WASTE_LEFT: u16 = 21;
+    pub const WASTE_CARD_WIDTH: u16 = 6;  // Card plus gap
+    pub const FOUNDATION_LEFT: u16 = 53;
+    pub const FOUNDATION_WIDTH: u16 = 5;
+    pub const TABLEAU_HEADER_ROW: u16 = 9;
+    pub const TABLEAU_TOP: u16 = 10;
+    pub const TABLEAU_LEFT: u16 = 2;
+    pub const TABLEAU_ROWS: usize = 12;   // Card rows shown before a column is clipped, without the HUD
+    pub const CLICK_SLACK: u16 = 2;       // Columns just outside a pile that still count as clicking it
+    pub const HUD_RAISE: u16 = 4;         // Rows the board moves up when the HUD replaces the title and stats
+    pub const BELOW_TABLEAU: u16 = 3;     // The "more" marker, hint and status rows
+    pub const CONTROLS_ROWS: u16 = 1;     // A short list of the main keys; ? shows the rest
+    pub const FULL_HEIGHT: u16 = 26;      // Rows needed for the whole screen, controls included
+
+    pub fn new(spacing: Spacing) -> Self {
+        Layout {
+            column_width: match spacing {
+                Spacing::Compact => 6,
+                Spacing::Spacious => 8,
+            },
+            left: 0,
+            hud: false,
+            height: u16::MAX,
+        }
+    }
+
+    // Screen row for a row of the usual layout, which the HUD moves up.
+    // The tableau's bottom edge stays put, so the rows freed go to the tableau.
+    pub fn y(&self, row: u16) -> u16 {
+        row - self.raise()
+    }
+
+    fn raise(&self) -> u16 {
+        if self.hud { Self::HUD_RAISE } else { 0 }
+    }
+
+    // Short terminals give up tableau rows, so the hint and status lines stay on screen
+    pub fn tableau_rows(&self) -> usize {
+        let fit = self.height.saturating_sub(self.y(Self::TABLEAU_TOP) + Self::BELOW_TABLEAU);
+        (Self::TABLEAU_ROWS + self.raise() as usize).min(fit as usize).max(1)
+    }
+
+    // The footer follows the tableau up when it is shortened
+    pub fn hint_row(&self) -> u16 {
+        self.y(Self::TABLEAU_TOP) + self.tableau_rows() as u16 + 1
+    }
+
+    pub fn status_row(&self) -> u16 {
+        self.hint_row() + 1
+    }
+
+    // Where the controls go, if the terminal has room for them
+    pub fn controls_row(&self) -> Option<u16> {
+        let row = self.status_row() + 1;
+        (row + Self::CONTROLS_ROWS <= self.height).then_some(row)
+    }
+
+    // The first row below everything else, if there are `rows` of them left
+    pub fn spare_row(&self, rows: u16) -> Option<u16> {
+        let row = self.status_row() + 1 + self.controls_row().map_or(0, |_| Self::CONTROLS_ROWS);
+        (row + rows <= self.height).then_some(row)
+    }
+
+    // Screen column for a position measured from the board's left edge
+    pub fn x(&self, offset: u16) -> u16 {
+        self.left + offset
+    }
+
+    pub fn column_x(&self, col: usize) -> u16 {
+        self.x(Self::TABLEAU_LEFT + col as u16 * self.column_width)
+    }
+
+    // The tableau column under board column `x`, if any. Clicks in the margin
+    // either side of the tableau go to the outer column.
+    pub fn column_at(&self, x: u16, columns: usize) -> Option<usize> {
+        let right = Self::TABLEAU_LEFT + self.tableau_width(columns) as u16;
+        if columns == 0 || x >= right + Self::CLICK_SLACK {
+            return None;
+        }
+        let col = (x.saturating_sub(Self::TABLEAU_LEFT) / self.column_width) as usize;
+        Some(col.min(columns - 1))
+    }
+
+    // The tableau row under screen row `y`, counting rows scrolled off the top.
+    // The column headers above count as the first row shown, and the marker row
+    // below as the last.
+    pub fn row_at(&self, y: u16, scroll: usize) -> Option<usize> {
+        let visible = (y + 1).checked_sub(self.y(Self::TABLEAU_TOP))?.saturating_sub(1) as usize;
+        (visible <= self.tableau_rows()).then_some(visible.min(self.tableau_rows() - 1) + scroll)
+    }
+
+    // Screen row for a tableau row, if it is scrolled into view
+    pub fn row_y(&self, row: usize, scroll: usize) -> Option<u16> {
+        let visible = row.checked_sub(scroll)?;
+        (visible < self.tableau_rows()).then_some(self.y(Self::TABLEAU_TOP) + visible as u16)
+    }
+
+    // Rows scrolled off the top of the tableau. Worked out from the game each time,
+    // so drawing and clicking always agree: normally none, but a selected run in a
+    // tall column is brought into view down to its bottom card.
+    pub fn tableau_scroll(&self, game: &GameState) -> usize {
+        match game.selected_card {
+            Some((PileType::Tableau, col, anchor)) => {
+                game.tableau[col].len().saturating_sub(self.tableau_rows()).min(anchor)
+            }
+            _ => 0,
+        }
+    }
+
+    // Screen column a pile starts at, which the keyboard cursor steps between
+    pub fn pile_x(&self, pile_type: PileType, index: usize) -> u16 {
+        match pile_type {
+            PileType::Stock => self.x(Self::STOCK_LEFT),
+            PileType::Waste => self.x(Self::WASTE_LEFT),
+            PileType::Foundation => self.x(Self::FOUNDATION_LEFT + index as u16 * Self::FOUNDATION_WIDTH),
+            PileType::Tableau => self.column_x(index),
+        }
+    }
+
+    pub fn tableau_width(&self, columns: usize) -> usize {
+        columns * self.column_width as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::Card;
+    use crate::rules::RulesConfig;
+
+    // Column 1 holds a face-down card under a whole suit-alternating run, King to Ace
+    fn tall_column() -> GameState {
+        let mut tableau = vec![Vec::new(); 7];
+        tableau[0] = ["kd", "KS", "QH", "JS", "TH", "9S", "8H", "7S", "6H", "5S", "4H", "3S", "2H", "AS"]
+            .iter()
+            .map(|code| Card::from_code(code).unwrap())
+            .collect();
+        GameState::with_fixed_tableau(tableau, RulesConfig::default(), 1).unwrap()
+    }
+
+    #[test]
+    fn row_at_counts_the_headers_as_the_first_row() {
+        let layout = Layout::default();
+        assert_eq!(layout.row_at(Layout::TABLEAU_HEADER_ROW - 1, 0), None);
+        assert_eq!(layout.row_at(Layout::TABLEAU_HEADER_ROW, 0), Some(0));
+        assert_eq!(layout.row_at(Layout::TABLEAU_TOP, 0), Some(0), "the first card");
+        assert_eq!(layout.row_at(Layout::TABLEAU_TOP + 3, 0), Some(3));
+        assert_eq!(layout.row_at(Layout::TABLEAU_TOP + 3, 2), Some(5), "rows scrolled off the top still count");
+    }
+
+    #[test]
+    fn row_at_stops_at_a_clipped_bottom() {
+        let layout = Layout { height: 20, ..Layout::default() };
+        assert_eq!(layout.tableau_rows(), 7);
+        let marker = Layout::TABLEAU_TOP + 7;
+        assert_eq!(layout.row_at(marker - 1, 0), Some(6));
+        assert_eq!(layout.row_at(marker, 0), Some(6), "the marker row stands for the last row shown");
+        assert_eq!(layout.row_at(marker + 1, 0), None);
+    }
+
+    #[test]
+    fn column_at_gives_margins_to_the_outer_columns() {
+        let layout = Layout::default();
+        assert_eq!(layout.column_at(0, 7), Some(0));
+        assert_eq!(layout.column_at(Layout::TABLEAU_LEFT + layout.column_width, 7), Some(1));
+        let right = Layout::TABLEAU_LEFT + layout.tableau_width(7) as u16;
+        assert_eq!(layout.column_at(right + Layout::CLICK_SLACK - 1, 7), Some(6));
+        assert_eq!(layout.column_at(right + Layout::CLICK_SLACK, 7), None);
+        assert_eq!(layout.column_at(0, 0), None);
+    }
+
+    #[test]
+    fn tableau_scroll_brings_a_selected_run_into_view() {
+        let layout = Layout::default();
+        let mut game = tall_column();
+        assert_eq!(layout.tableau_scroll(&game), 0, "nothing selected");
+        game.selected_card = Some((PileType::Tableau, 0, 1));
+        assert_eq!(layout.tableau_scroll(&game), 1, "no further than the run's first card");
+        game.selected_card = Some((PileType::Tableau, 0, 10));
+        assert_eq!(layout.tableau_scroll(&game), 2, "just far enough to show the bottom card");
+    }
+}
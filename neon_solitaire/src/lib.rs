@@ -0,0 +1,16 @@
+pub mod card;
+pub mod game;
+pub mod i18n;
+pub mod display;
+pub mod input;
+pub mod layout;
+pub mod moves;
+pub mod timer;
+pub mod settings;
+pub mod sound;
+pub mod tutorial;
+pub mod rules;
+pub mod stats;
+pub mod solver;
+pub mod race;
+pub mod replay;
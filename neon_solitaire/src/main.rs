@@ -1,132 +1,1095 @@
-mod card;
-mod game;
-mod display;
-mod input;
-mod moves;
-
-use game::GameState;
-use display::Display;
-use input::{InputHandler, InputAction, handle_game_action};
-use moves::auto_complete;
+use neon_solitaire::card::Rank;
+use neon_solitaire::game::{GameOverReason, GameState, PileType};
+use neon_solitaire::display::{ColorDepth, DealLoop, Display};
+use neon_solitaire::i18n::Language;
+use neon_solitaire::input::{ActionOutcome, InputHandler, InputAction, convert_mouse_to_game_position, cursor_action, handle_game_action};
+use neon_solitaire::layout::Layout;
+use neon_solitaire::moves::{draw_nudge, only_waste_move, safe_foundation_move};
+use neon_solitaire::race::Race;
+use neon_solitaire::replay::{describe_step, Replay};
+use neon_solitaire::rules::RulesConfig;
+use neon_solitaire::settings::Settings;
+use neon_solitaire::solver::{find_winnable_seed, finish, finish_step, par};
+use neon_solitaire::sound::{ring_bell, BellEvent};
+use neon_solitaire::stats::Stats;
+use neon_solitaire::tutorial::{Tutorial, TutorialStep};
 use crossterm::{
     execute,
-    terminal::{self, Clear, ClearType},
+    terminal::{self, Clear, ClearType, EnableLineWrap},
     cursor::{MoveTo, Show, Hide},
-    style::{Color, Print, SetForegroundColor, ResetColor},
-    event::{self, Event, KeyCode},
+    style::{Color, Print, ResetColor},
+    event::{self, DisableMouseCapture, Event, KeyCode},
 };
-use std::io::stdout;
+use std::cmp::Ordering;
+use std::io::{stdin, stdout, IsTerminal, Write};
 use std::time::{Duration, Instant};
 use std::thread;
 
+// Player preferences, read once at startup
+const SETTINGS_PATH: &str = "neon_solitaire_settings.json";
+
+// Lifetime statistics across all games
+const STATS_PATH: &str = "neon_solitaire_stats.json";
+
+// Finished games are appended here, one row per game
+const RESULTS_CSV_PATH: &str = "neon_solitaire_results.csv";
+
+// Status messages (such as why a move was refused) fade after this long
+const STATUS_MESSAGE_SECS: u64 = 2;
+
+// How often the selected cards switch shade when animations are on
+const SELECTION_PULSE_MS: u64 = 500;
+
+// Pause between cards when the game plays itself (auto-complete and the safe-card sweep)
+const AUTO_PLAY_STEP_MS: u64 = 200;
+
+// A peek lasts this long after V is pressed. Holding V keeps it going through
+// the terminal's key repeat, so letting go hides the cards again.
+const PEEK_HOLD_MS: u64 = 700;
+
+// How long the cards from the last draw stay marked on the waste, unless something else happens first
+const JUST_DRAWN_MS: u64 = 1500;
+
+// How long a score change stays shown by its pile
+const SCORE_POPUP_MS: u64 = 1000;
+
+// With no input for this long the game pauses itself so the timer stays honest
+const IDLE_PAUSE_SECS: u64 = 120;
+
+// X writes the current position here, as text another player can load with --position
+const POSITION_PATH: &str = "neon_solitaire_position.txt";
+
+// M writes the game so far here, for --replay to step through
+const REPLAY_PATH: &str = "neon_solitaire_replay.txt";
+
+// Set to anything to overlay pile positions and card indices, for working on click mapping
+const DEBUG_LAYOUT_VAR: &str = "NEON_SOLITAIRE_DEBUG_LAYOUT";
+
+// Deals tried by --winnable before settling for an unchecked one
+const WINNABLE_ATTEMPTS: u32 = 50;
+
+// Positions the solver looks at to set a deal's par; deals it can't finish in time have none
+const PAR_NODE_BUDGET: usize = 5_000;
+
+const USAGE: &str = "\
+Usage: neon_solitaire [OPTIONS]
+
+Options:
+  --seed <N>      Deal the game with this seed
+  --draw <N>      Cards drawn at a time: 1, 3 (default) or all, an easy mode
+                  that turns the whole stock over and isn't counted in the stats
+  --vegas         Vegas scoring with limited passes through the stock
+  --strict        Cards sent up to the foundations can't be taken back down
+  --max-moves <N> Challenge mode: the game ends after N moves
+  --winnable      Only deal games the solver can finish (ignored with --seed)
+  --race          Two players race through the same deal side by side
+  --endgame <N>   Practice the endgame: foundations start built up to rank N (1-12)
+  --position <P>  Play from a position string saved with X (quote it)
+  --shuffle-stock With --position: keep its tableau but deal a fresh stock each game
+  --manual-flip   Leave uncovered cards face down until you turn them over
+  --replay <FILE> Step through a game saved with M, using the arrow keys
+  --ascii         Draw the board with plain ASCII characters
+  --color         Use colours even if NO_COLOR is set
+  --no-color      No colours; red cards are marked with *
+  --no-mouse      Keyboard only, leaving the terminal's text selection working
+  --reset-stats   Erase lifetime statistics and exit
+  -h, --help      Show this message";
+
+// Startup options taken from the command line
+#[derive(Debug, Default)]
+struct LaunchOptions {
+    seed: Option<u64>,
+    draw_count: Option<usize>,
+    draw_all: bool,          // Easy mode, set by --draw all
+    max_moves: Option<u32>,
+    vegas: bool,
+    strict: bool, // No foundation-to-tableau moves; also set from the settings
+    winnable: bool,
+    race: bool,
+    endgame: Option<Rank>, // Starting foundation height for endgame practice
+    position: Option<String>, // Shared position to start from, checked before the game starts
+    replay: Option<String>, // Replay file to view instead of playing
+    shuffle_stock: bool, // Only with a position: its tableau stays, the rest is reshuffled
+    manual_flip: bool,
+    ascii: bool,
+    color: Option<bool>, // Set by --color / --no-color
+    no_mouse: bool,
+    reset_stats: bool,
+    help: bool,
+    recycle_penalty: Option<u32>, // From settings rather than the command line
+    hint_penalty: u32,            // Likewise
+    combo: bool,                  // Likewise
+    undo_limit: Option<usize>,    // Likewise
+    language: Language,           // Likewise, for the messages each game shows
+}
+
+impl LaunchOptions {
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = LaunchOptions::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => {
+                    let value = args.next().ok_or("--seed needs a number")?;
+                    options.seed = Some(value.parse().map_err(|_| format!("Invalid seed: {}", value))?);
+                }
+                "--draw" => {
+                    let value = args.next().ok_or("--draw needs 1, 3 or all")?;
+                    match value.as_str() {
+                        "1" => options.draw_count = Some(1),
+                        "3" => options.draw_count = Some(3),
+                        "all" => options.draw_all = true,
+                        _ => return Err(format!("Invalid draw count: {} (expected 1, 3 or all)", value)),
+                    }
+                }
+                "--max-moves" => {
+                    let value = args.next().ok_or("--max-moves needs a number")?;
+                    match value.parse() {
+                        Ok(max) if max > 0 => options.max_moves = Some(max),
+                        _ => return Err(format!("Invalid move limit: {}", value)),
+                    }
+                }
+                "--endgame" => {
+                    let value = args.next().ok_or("--endgame needs a rank from 1 to 12")?;
+                    match value.parse().ok().filter(|&rank| rank < Rank::King as u8).and_then(Rank::from_value) {
+                        Some(rank) => options.endgame = Some(rank),
+                        None => return Err(format!("Invalid endgame rank: {} (expected 1 to 12)", value)),
+                    }
+                }
+                "--position" => options.position = Some(args.next().ok_or("--position needs a position string")?),
+                "--shuffle-stock" => options.shuffle_stock = true,
+                "--manual-flip" => options.manual_flip = true,
+                "--vegas" => options.vegas = true,
+                "--strict" => options.strict = true,
+                "--winnable" => options.winnable = true,
+                "--race" => options.race = true,
+                "--replay" => options.replay = Some(args.next().ok_or("--replay needs a file saved with M")?),
+                "--ascii" => options.ascii = true,
+                "--color" => options.color = Some(true),
+                "--no-color" => options.color = Some(false),
+                "--no-mouse" => options.no_mouse = true,
+                "--reset-stats" => options.reset_stats = true,
+                "-h" | "--help" => options.help = true,
+                _ => return Err(format!("Unknown option: {}", arg)),
+            }
+        }
+        if options.shuffle_stock && options.position.is_none() {
+            return Err("--shuffle-stock needs a --position to keep the tableau of".to_string());
+        }
+        Ok(options)
+    }
+
+    // An explicit --color or --no-color wins (the last one given, if both are).
+    // Without either, a non-empty NO_COLOR variable turns colour off, as https://no-color.org asks.
+    fn use_color(&self) -> bool {
+        self.color.unwrap_or_else(|| std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()))
+    }
+
+    fn rules(&self) -> RulesConfig {
+        let mut rules = RulesConfig {
+            max_moves: self.max_moves,
+            combo: self.combo,
+            auto_flip: !self.manual_flip,
+            undo_limit: self.undo_limit.unwrap_or(RulesConfig::UNDO_LIMIT),
+            draw_all: self.draw_all,
+            allow_foundation_to_tableau: !self.strict,
+            ..RulesConfig::default()
+        };
+        if self.vegas {
+            rules = rules.vegas(self.draw_count.unwrap_or(3));
+        } else {
+            rules.points.hint_penalty = self.hint_penalty as i32; // Hints stay free in Vegas
+        }
+        if let Some(penalty) = self.recycle_penalty {
+            rules.points.recycle_penalty = penalty as i32;
+        }
+        rules
+    }
+
+    fn deal(&self, seed: u64) -> GameState {
+        let mut game = match self.endgame {
+            Some(height) => GameState::new_endgame(self.rules(), seed, height),
+            None => GameState::new_with_rules(self.rules(), seed),
+        };
+        if let Some(draw_count) = self.draw_count {
+            game.draw_count = draw_count;
+        }
+        game.strings = self.language.strings();
+        game
+    }
+
+    // Set-up games that don't count towards the statistics or results file
+    fn practice(&self) -> bool {
+        self.endgame.is_some() || self.position.is_some() || self.draw_all
+    }
+
+    fn new_game(&self) -> GameState {
+        if let Some(position) = &self.position {
+            let mut game = GameState::from_position_string(position, self.rules()).expect("position checked at startup");
+            if self.shuffle_stock {
+                let draw_count = game.draw_count;
+                let seed = self.seed.unwrap_or_else(rand::random);
+                game = GameState::with_fixed_tableau(game.tableau, game.rules, seed).expect("a valid position's tableau fits the deck");
+                game.draw_count = draw_count;
+            }
+            game.strings = self.language.strings();
+            return with_par(game);
+        }
+        match self.seed {
+            Some(seed) => with_par(self.deal(seed)),
+            None => self.fresh_game(),
+        }
+    }
+
+    // A newly shuffled deal, never the --seed or --position game again
+    fn fresh_game(&self) -> GameState {
+        let seed = if self.winnable {
+            let start = rand::random();
+            find_winnable_seed(|seed| self.deal(seed), start, WINNABLE_ATTEMPTS).unwrap_or(start)
+        } else {
+            rand::random()
+        };
+        with_par(self.deal(seed))
+    }
+}
+
+// Par is worked out once, when the game is dealt, so it can't change as the game goes
+fn with_par(mut game: GameState) -> GameState {
+    game.par = par(&game, PAR_NODE_BUDGET);
+    game
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize terminal and display
-    let display = Display::new();
-    let mut input_handler = InputHandler::new();
+    // Everything here happens before the terminal goes into raw mode
+    let mut options = match LaunchOptions::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            std::process::exit(2);
+        }
+    };
+    if options.help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+    if options.reset_stats {
+        return reset_stats();
+    }
+    if let Some(position) = &options.position {
+        if let Err(message) = GameState::from_position_string(position, options.rules()) {
+            eprintln!("Invalid position: {}", message);
+            std::process::exit(2);
+        }
+    }
+    // A replay is read and played through in full before the viewer opens
+    let replay = match &options.replay {
+        Some(path) => match load_replay(path, options.rules()) {
+            Ok(loaded) => Some(loaded),
+            Err(message) => {
+                eprintln!("Invalid replay {}: {}", path, message);
+                std::process::exit(2);
+            }
+        },
+        None => None,
+    };
     
-    // Set up panic handler to clean up terminal on crash
-    std::panic::set_hook(Box::new(|_| {
-        let _ = terminal::disable_raw_mode();
-        let _ = execute!(stdout(), Show, ResetColor, Clear(ClearType::All));
+    // Raw mode and mouse capture only make sense on a real terminal
+    if !stdout().is_terminal() {
+        eprintln!("Neon Solitaire needs an interactive terminal; stdout is redirected to a file or pipe.");
+        eprintln!("Run it directly in a terminal window to play.");
+        std::process::exit(1);
+    }
+    
+    let settings = Settings::load(SETTINGS_PATH);
+    options.recycle_penalty = settings.recycle_penalty;
+    options.hint_penalty = settings.hint_penalty;
+    options.combo = settings.combo;
+    options.undo_limit = Some(settings.undo_limit);
+    options.language = settings.language;
+    options.strict |= !settings.allow_foundation_to_tableau;
+    
+    // Set up panic handler to clean up terminal on crash, then report the panic as usual
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        default_hook(info);
     }));
     
-    // Initialize terminal
+    // Initialize terminal and display; from here on every way out of main restores it
+    let terminal_guard = TerminalGuard;
+    let mut display = Display::new();
+    display.ascii = options.ascii;
+    display.monochrome = !options.use_color();
+    display.color_depth = ColorDepth::detect();
+    display.card_back = settings.card_back;
+    display.palette = settings.suit_palette();
+    display.layout = Layout::new(settings.spacing);
+    display.layout.hud = settings.hud;
+    display.draw_odds = settings.draw_odds;
+    display.score_ceiling = settings.score_ceiling;
+    display.score_popups = settings.animations;
+    display.show_waste_list = settings.waste_list;
+    display.strings = settings.language.strings();
+    display.debug_layout = std::env::var_os(DEBUG_LAYOUT_VAR).is_some_and(|value| !value.is_empty());
+    let mut input_handler = InputHandler::with_mouse(settings.mouse && !options.no_mouse);
     terminal::enable_raw_mode()?;
     display.init_terminal()?;
     
-    // Show welcome screen and WAIT for key press
-    show_welcome_screen()?;
-    wait_for_keypress()?;
+    if let Some((replay, boards)) = replay {
+        let result = run_replay(&replay, &boards, &mut display);
+        drop(terminal_guard);
+        return result;
+    }
+    
+    // A race replaces the usual single game, tutorial and statistics
+    if options.race {
+        let result = run_race(&options, settings.confirm_recycle, &mut display, &mut input_handler);
+        drop(terminal_guard);
+        match result? {
+            Some(player) => println!("\n🏆 Player {} wins the race!", player + 1),
+            None => println!("\nRace abandoned."),
+        }
+        return Ok(());
+    }
+    
+    // Show welcome screen and WAIT for key press; T starts the tutorial instead
+    show_welcome_screen(&display)?;
+    let mut tutorial = match wait_for_keypress()? {
+        KeyCode::Char('t') | KeyCode::Char('T') => Some(Tutorial::new()),
+        _ => None,
+    };
     
     // Create new game
-    let mut game = GameState::new();
+    let mut game = match tutorial {
+        Some(_) => Tutorial::create_game(),
+        None => options.new_game(),
+    };
+    display.tutorial_prompt = tutorial.as_ref().map(|t| t.prompt());
     let mut last_draw = Instant::now();
     let mut auto_completing = false;
+    let mut sweeping_safe = false;
+    let mut auto_complete_offered = false; // Asked already since auto-complete last became possible
+    let mut finish_checked = None; // The last position is_trivially_won was asked about, and its answer
     let mut force_redraw = true;
+    let mut shown_secs = game.elapsed_secs();
+    let mut last_input = Instant::now();
+    let mut idle_paused = false;
+    let mut last_pulse = Instant::now();
+    let mut pulse_bright = false;
+    let mut peek_until = Instant::now();
+    let mut drawn_at = Instant::now();
+    let mut save_results = Vec::new(); // Results file and statistics writes, reported once the terminal is restored
     
-    // Initial draw
-    display.draw_game(&game)?;
-    
-    // Main game loop
+    // Main game loop, which measures the terminal and draws the board on its first pass
     loop {
+        // The board fits itself to the terminal's height, so a resize means starting the screen afresh
+        let (_, height) = terminal::size()?;
+        if height != display.layout.height {
+            display.layout.height = height;
+            if display.layout.controls_row().is_none() {
+                game.show_status(format!("The terminal is {} rows; {} shows the controls too", height, Layout::FULL_HEIGHT));
+            }
+            display.clear_screen()?;
+            force_redraw = true;
+        }
+        
         // Check for win
-        if game.is_won() && !auto_completing {
+        if game.is_won() && !auto_completing && !sweeping_safe {
+            game.timer.pause(); // Stop the clock at the winning move
             display.draw_win_animation()?;
+            if settings.bell {
+                ring_bell(BellEvent::Win)?;
+            }
+            // Keys still held from the winning moves shouldn't answer the win screen
+            thread::sleep(Duration::from_secs(1));
+            while event::poll(Duration::ZERO)? {
+                event::read()?;
+            }
+            match wait_for_keypress()? {
+                // Back into the finished game; redoing to the winning move brings this screen back
+                KeyCode::Char('z') | KeyCode::Char('Z') => {
+                    game.undo();
+                    game.timer.resume();
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') if tutorial.is_none() => {
+                    if !options.practice() {
+                        save_results.push(record_result(&game));
+                    }
+                    game = match display.deal_loop.as_mut() {
+                        Some(deal_loop) => {
+                            deal_loop.deal += 1;
+                            deal_loop.wins += 1;
+                            options.fresh_game()
+                        }
+                        None => options.new_game(),
+                    };
+                    auto_complete_offered = false;
+                }
+                _ => break,
+            }
+            display.clear_screen()?;
+            display.draw_game(&game)?;
+            continue;
+        }
+        
+        // Challenge mode: the last allowed move has been made without winning
+        if let Some(max_moves) = game.rules.max_moves.filter(|_| game.out_of_moves() && !auto_completing && !sweeping_safe) {
+            game.timer.pause();
+            display.draw_out_of_moves(max_moves)?;
             thread::sleep(Duration::from_secs(3));
             break;
         }
         
+        // While paused the board stays hidden; P resumes, or any key after an idle pause
+        if game.timer.is_paused() {
+            let action = input_handler.poll_input();
+            if matches!(action, InputAction::QuitNow) {
+                break;
+            }
+            let resume = match action {
+                InputAction::None => false,
+                InputAction::Pause => true,
+                _ => idle_paused,
+            };
+            if resume {
+                game.timer.resume();
+                idle_paused = false;
+                last_input = Instant::now();
+                display.clear_screen()?;
+                display.draw_game(&game)?;
+            }
+            thread::sleep(Duration::from_millis(10));
+            continue;
+        }
+        
+        // Nobody has touched the game for a while, stop the clock
+        if last_input.elapsed() >= Duration::from_secs(IDLE_PAUSE_SECS) {
+            game.timer.pause();
+            idle_paused = true;
+            display.draw_idle_screen()?;
+            continue;
+        }
+        
         // Auto-complete mode
-        if auto_completing {
-            if last_draw.elapsed() > Duration::from_millis(200) {
-                if !auto_complete(&mut game) {
-                    auto_completing = false;
+        if auto_completing && last_draw.elapsed() > Duration::from_millis(AUTO_PLAY_STEP_MS) {
+            if !finish_step(&mut game) {
+                auto_completing = false;
+            }
+            display.draw_game(&game)?;
+            last_draw = Instant::now();
+        }
+        
+        // Safe-card sweep: one card at a time until nothing left is provably safe to send up
+        if sweeping_safe && last_draw.elapsed() > Duration::from_millis(AUTO_PLAY_STEP_MS) {
+            match safe_foundation_move(&game) {
+                Some(mut mv) => {
+                    mv.execute(&mut game);
                 }
-                display.draw_game(&game)?;
-                last_draw = Instant::now();
+                None => sweeping_safe = false,
+            }
+            display.draw_game(&game)?;
+            last_draw = Instant::now();
+        }
+        
+        // Offer to finish once, the moment it becomes possible. Declining leaves the
+        // player in control; undoing back out of it and returning asks again.
+        // The solver is only asked again once the position has changed.
+        let position = game.position_key();
+        let finishable = match &finish_checked {
+            Some((checked, answer)) if *checked == position => *answer,
+            _ => {
+                let answer = game.is_trivially_won();
+                finish_checked = Some((position, answer));
+                answer
+            }
+        };
+        if !finishable {
+            auto_complete_offered = false;
+        } else if settings.offer_auto_complete && !auto_complete_offered && !auto_completing && !sweeping_safe && tutorial.is_none() {
+            auto_complete_offered = true;
+            let question = if game.can_auto_complete() {
+                display.strings.finish_face_up
+            } else {
+                display.strings.finish_sure_win
+            };
+            match finish_prompt(&display, question, display.strings.finish_myself)? {
+                Some(Finish::Animated) => auto_completing = true,
+                Some(Finish::Instant) => {
+                    finish(&mut game);
+                }
+                None => {}
             }
+            force_redraw = true;
         }
         
         // Handle input
-        let action = input_handler.poll_input();
+        let mut action = input_handler.poll_input();
+        if !matches!(action, InputAction::None) {
+            last_input = Instant::now();
+        }
+        
+        // The tutorial only lets through what the current step teaches,
+        // and once finished any key moves on to a real game
+        if let Some(t) = &tutorial {
+            if t.step == TutorialStep::Done && !matches!(action, InputAction::None) {
+                tutorial = None;
+                display.tutorial_prompt = None;
+                game = options.new_game();
+                display.clear_screen()?;
+                display.draw_game(&game)?;
+                continue;
+            }
+            if !t.allows(&action) {
+                action = InputAction::None;
+            }
+        }
+        
+        if let InputAction::MouseClick(x, y) | InputAction::MouseRightClick(x, y) = action {
+            if display.debug_layout {
+                display.last_click = Some((x, y));
+                force_redraw = true;
+            }
+        }
         
         // Store state before action
-        let old_selected = game.selected_card;
         let old_moves = game.move_count;
-        let old_score = game.score;
         let old_waste_len = game.waste.len();
         let old_stock_len = game.stock.len();
+        let old_status_at = game.status_message.as_ref().map(|(_, shown)| *shown);
+        let old_foundation_cards: usize = game.foundations.iter().map(|f| f.len()).sum();
+        game.move_rejected = false;
+        
+        if let Some(question) = recycle_question(&game, action, &display.layout, settings.confirm_recycle) {
+            if !confirm_prompt(&display, &question, display.strings.yes_recycle, display.strings.keep_playing)? {
+                action = InputAction::None;
+            }
+            force_redraw = true;
+        }
         
-        let should_quit = match action {
-            InputAction::None => false,
+        let outcome = match action {
+            InputAction::None => ActionOutcome::Unchanged,
+            InputAction::QuitNow => break,
             InputAction::Quit => {
-                if confirm_quit()? {
+                if !settings.confirm_quit || confirm_prompt(&display, "Are you sure you want to quit?", "Yes, quit the game", "No, keep playing")? {
                     break;
                 }
-                force_redraw = true;
-                false
+                ActionOutcome::Changed
+            }
+            InputAction::Hint => {
+                let outcome = handle_game_action(&mut game, action, &display.layout);
+                // No hint at all, not even a draw, means the deal is lost, so offer another
+                if game.hint.is_none()
+                    && game.game_over_reason() == GameOverReason::Stuck
+                    && tutorial.is_none()
+                    && confirm_prompt(&display, "No legal moves remain - game over. Deal again?", "Yes, new game", "No, undo and try another way")?
+                {
+                    if !options.practice() {
+                        save_results.push(record_result(&game));
+                    }
+                    game = options.new_game();
+                    auto_complete_offered = false;
+                    display.clear_screen()?;
+                    ActionOutcome::Changed
+                } else {
+                    outcome
+                }
+            }
+            // The board moves, so clear what was drawn where it used to be
+            InputAction::ToggleHud => {
+                display.layout.hud = !display.layout.hud;
+                display.clear_screen()?;
+                ActionOutcome::Changed
+            }
+            InputAction::ExportPosition => {
+                let message = match std::fs::write(POSITION_PATH, game.to_position_string() + "\n") {
+                    Ok(()) => format!("Position saved to {}", POSITION_PATH),
+                    Err(e) => format!("Could not save position: {}", e),
+                };
+                game.show_status(message);
+                ActionOutcome::Changed
+            }
+            // Turning it off leaves the current game to play out as usual
+            InputAction::ToggleDealLoop => {
+                display.deal_loop = match display.deal_loop {
+                    Some(_) => None,
+                    None => Some(DealLoop { deal: 1, wins: 0 }),
+                };
+                display.clear_screen()?;
+                ActionOutcome::Changed
+            }
+            InputAction::ToggleTeacher => {
+                display.teacher = !display.teacher;
+                ActionOutcome::Changed
+            }
+            // A look at the lifetime numbers without leaving the game; the clock keeps running
+            InputAction::QuickStats => {
+                display.draw_stats_overlay(&Stats::load(STATS_PATH))?;
+                wait_for_keypress()?;
+                ActionOutcome::Changed
+            }
+            InputAction::KeyReference => {
+                display.draw_key_reference(input_handler.mouse_enabled)?;
+                wait_for_keypress()?;
+                display.clear_screen()?;
+                ActionOutcome::Changed
+            }
+            InputAction::ToggleHistory => {
+                display.show_history = !display.show_history;
+                display.clear_screen()?;
+                ActionOutcome::Changed
+            }
+            // The legal moves panel belongs to the display, not the game
+            InputAction::ToggleMoveList => {
+                display.show_move_list = !display.show_move_list;
+                display.move_list_scroll = 0;
+                display.clear_screen()?;
+                ActionOutcome::Changed
+            }
+            InputAction::ScrollMoveList(down) if display.show_move_list => {
+                display.scroll_move_list(&game, down);
+                ActionOutcome::Changed
+            }
+            // With the move list closed, the same keys scroll the waste panel
+            InputAction::ScrollMoveList(down) if display.show_waste_list => {
+                display.scroll_waste_list(&game, down);
+                ActionOutcome::Changed
+            }
+            InputAction::ExportReplay => {
+                let message = match std::fs::write(REPLAY_PATH, Replay::of(&game).to_text()) {
+                    Ok(()) => format!("Replay of {} moves saved to {}", game.history.len(), REPLAY_PATH),
+                    Err(e) => format!("Could not save replay: {}", e),
+                };
+                game.show_status(message);
+                ActionOutcome::Changed
+            }
+            InputAction::Pause => {
+                game.timer.pause();
+                display.draw_pause_screen()?;
+                ActionOutcome::Unchanged
             }
             InputAction::AutoMove => {
-                if !game.auto_move_to_foundation() {
-                    auto_completing = true;
+                game.clear_hint();
+                if game.auto_move_to_foundation().is_none() {
+                    game.show_status(display.strings.nothing_to_foundation.to_string());
                 }
-                force_redraw = true;
-                false
+                ActionOutcome::Changed
             }
-            _ => handle_game_action(&mut game, action)
+            InputAction::SendSafeToFoundations => {
+                game.clear_hint();
+                game.selected_card = None;
+                if safe_foundation_move(&game).is_some() {
+                    sweeping_safe = true;
+                } else {
+                    game.show_status(display.strings.nothing_safe.to_string());
+                }
+                ActionOutcome::Changed
+            }
+            // Reads the piles out without touching the game or the selection
+            InputAction::Info => {
+                game.show_status(game.summary());
+                ActionOutcome::Changed
+            }
+            InputAction::Peek => {
+                if game.peek_allowed() {
+                    game.peeked = true;
+                    display.peek = true;
+                    peek_until = Instant::now() + Duration::from_millis(PEEK_HOLD_MS);
+                } else {
+                    game.show_status(display.strings.no_peeking.to_string());
+                }
+                ActionOutcome::Changed
+            }
+            // Finishing the game for the player is only offered once it can't go wrong, and asked first
+            InputAction::AutoComplete => {
+                game.clear_hint();
+                if !game.is_trivially_won() {
+                    game.show_status(display.strings.not_finishable.to_string());
+                } else {
+                    match finish_prompt(&display, display.strings.finish_now, display.strings.keep_playing)? {
+                        Some(Finish::Animated) => auto_completing = true,
+                        Some(Finish::Instant) => {
+                            finish(&mut game);
+                        }
+                        None => {}
+                    }
+                }
+                ActionOutcome::Changed
+            }
+            _ => handle_game_action(&mut game, action, &display.layout)
         };
         
-        if should_quit {
-            break;
+        match outcome {
+            ActionOutcome::Quit => break,
+            ActionOutcome::Won => continue, // The win screen takes over at the top of the loop
+            ActionOutcome::Changed => force_redraw = true,
+            ActionOutcome::Unchanged => {}
+        }
+        
+        // Deal until you win: a game that can't go on is recorded and replaced straight away
+        let board_changed = game.move_count != old_moves || game.stock.len() != old_stock_len;
+        if let Some(deal_loop) = display.deal_loop.as_mut().filter(|_| board_changed && !auto_completing && !sweeping_safe) {
+            let reason = game.game_over_reason();
+            if matches!(reason, GameOverReason::Stuck | GameOverReason::MoveLimit) {
+                if !options.practice() {
+                    save_results.push(record_result(&game));
+                }
+                deal_loop.deal += 1;
+                game = options.fresh_game();
+                game.show_status(format!(
+                    "The last deal {}; here is deal {}",
+                    if reason == GameOverReason::Stuck { "got stuck" } else { "ran out of moves" },
+                    deal_loop.deal
+                ));
+                auto_complete_offered = false;
+                display.clear_screen()?;
+                force_redraw = true;
+                continue;
+            }
+        }
+        
+        // Bring newly drawn cards onto the waste one at a time. Any input skips
+        // to the end, and is then handled as usual.
+        let drawn = if game.stock.len() < old_stock_len { game.waste.len().saturating_sub(old_waste_len) } else { 0 };
+        if drawn > 0 {
+            display.just_drawn = drawn;
+            drawn_at = Instant::now();
+            // Shown like a hint, but free: the player didn't ask, and nothing is played
+            if settings.suggest_waste_move && tutorial.is_none() {
+                game.hint = only_waste_move(&game);
+            }
+        } else if display.just_drawn > 0 && !matches!(action, InputAction::None) {
+            display.just_drawn = 0;
+            force_redraw = true;
+        }
+        if drawn > 0 && settings.animations && settings.draw_animation_ms > 0 {
+            display.draw_game(&game)?;
+            for shown in old_waste_len..game.waste.len() {
+                display.draw_waste_frame(&game, shown)?;
+                if event::poll(Duration::from_millis(settings.draw_animation_ms))? {
+                    break;
+                }
+            }
+            force_redraw = true;
+        }
+        
+        if let Some(t) = tutorial.as_mut() {
+            if t.advance(&game) {
+                display.tutorial_prompt = Some(t.prompt());
+                force_redraw = true;
+            }
+        }
+        
+        // A successful move replaces any complaint about the previous attempt,
+        // otherwise the message simply times out
+        let status_expired = game.status_message.as_ref()
+            .is_some_and(|(_, shown)| shown.elapsed() >= Duration::from_secs(STATUS_MESSAGE_SECS));
+        if (game.move_count > old_moves || status_expired) && game.status_message.take().is_some() {
+            force_redraw = true;
+        }
+        
+        // A finished suit gets its own moment before the game is won
+        for f in game.newly_completed_foundations() {
+            if let Some(king) = game.foundations[f].last() {
+                let suit = king.suit;
+                game.show_status(format!("{0} {1} complete! {0}", suit.symbol(), suit.name()));
+            }
+        }
+        if game.move_count > old_moves && game.status_message.is_none() {
+            if let Some(nudge) = draw_nudge(&game) {
+                game.show_status(nudge.to_string());
+            }
+        }
+        if game.status_message.as_ref().map(|(_, shown)| *shown) != old_status_at {
+            force_redraw = true;
+        }
+        
+        if settings.bell {
+            let foundation_cards: usize = game.foundations.iter().map(|f| f.len()).sum();
+            if game.move_rejected {
+                ring_bell(BellEvent::IllegalMove)?;
+            } else if foundation_cards > old_foundation_cards {
+                ring_bell(BellEvent::Foundation)?;
+            } else if game.move_count > old_moves {
+                ring_bell(BellEvent::Move)?;
+            }
+        }
+        
+        if display.just_drawn > 0 && drawn_at.elapsed() >= Duration::from_millis(JUST_DRAWN_MS) {
+            display.just_drawn = 0;
+            force_redraw = true;
+        }
+        
+        if game.score_popup.is_some_and(|(_, _, _, at)| at.elapsed() >= Duration::from_millis(SCORE_POPUP_MS)) {
+            game.score_popup = None;
+            force_redraw = true;
+        }
+        
+        if display.peek && Instant::now() >= peek_until {
+            display.peek = false;
+            force_redraw = true;
         }
         
-        // Only redraw if something changed
-        if force_redraw || 
-           old_selected != game.selected_card ||
-           old_moves != game.move_count ||
-           old_score != game.score ||
-           old_waste_len != game.waste.len() ||
-           old_stock_len != game.stock.len() {
+        // Only redraw if something changed (including the clock ticking over)
+        if force_redraw || shown_secs != game.elapsed_secs() {
             display.draw_game(&game)?;
             force_redraw = false;
+            shown_secs = game.elapsed_secs();
+            pulse_bright = false; // A full redraw paints the normal shade
+            last_pulse = Instant::now();
+        }
+        
+        // Pulse the selection by repainting only its cells, never the whole board.
+        // Reverse video can't pulse, so monochrome mode stays still.
+        if settings.animations
+            && !display.monochrome
+            && game.selected_card.is_some()
+            && last_pulse.elapsed() >= Duration::from_millis(SELECTION_PULSE_MS)
+        {
+            pulse_bright = !pulse_bright;
+            display.draw_selection_pulse(&game, pulse_bright)?;
+            last_pulse = Instant::now();
         }
         
         // Small delay to prevent CPU spinning
         thread::sleep(Duration::from_millis(10));
     }
     
+    // Record the result before cleanup, but only report problems once the terminal is restored.
+    // The scripted tutorial deal and practice set-ups aren't real games, so they are left out.
+    if tutorial.is_none() && !options.practice() {
+        save_results.push(record_result(&game));
+    }
+    
     // Cleanup
-    display.cleanup_terminal()?;
-    input_handler.cleanup();
-    terminal::disable_raw_mode()?;
+    drop(terminal_guard);
     
     // Show final stats
     show_final_stats(&game);
     
+    for (csv_result, stats_result) in save_results {
+        if let Err(e) = csv_result {
+            eprintln!("\n⚠ Could not save game result to {}: {}", RESULTS_CSV_PATH, e);
+        }
+        if let Err(e) = stats_result {
+            eprintln!("\n⚠ Could not save statistics to {}: {}", STATS_PATH, e);
+        }
+    }
+    
     Ok(())
 }
 
-fn show_welcome_screen() -> Result<(), Box<dyn std::error::Error>> {
+// Adds a finished game to the results file and the lifetime statistics
+fn record_result(game: &GameState) -> (std::io::Result<()>, std::io::Result<()>) {
+    let mut stats = Stats::load(STATS_PATH);
+    stats.record_game(game.is_won(), game.score, game.elapsed_secs());
+    if game.peeked {
+        stats.peeked_games += 1;
+    }
+    (game.append_result_csv(RESULTS_CSV_PATH), stats.save(STATS_PATH))
+}
+
+// Two copies of one deal, played side by side until a player wins or the race is abandoned.
+// Returns the winning player, if any.
+fn run_race(options: &LaunchOptions, confirm_recycle: bool, display: &mut Display, input_handler: &mut InputHandler) -> Result<Option<usize>, Box<dyn std::error::Error>> {
+    let mut race = Race::new(options.new_game());
+    display.layout.hud = false; // Race screens use the rows above the boards for each player's keys
+    let mut drawn_size = None; // Terminal size the screen was last laid out for
+    let mut shown_secs = 0;
+    
+    loop {
+        // Both clocks stop while the terminal is too small to show the boards
+        let (width, height) = terminal::size()?;
+        let fits = Race::fits(width, height);
+        let mut redraw = false;
+        if drawn_size != Some((width, height)) {
+            drawn_size = Some((width, height));
+            // Both boards share one layout, fitted to the terminal like the single game's
+            display.layout.height = height;
+            for board in &mut race.boards {
+                if fits { board.timer.resume() } else { board.timer.pause() }
+            }
+            if fits {
+                display.clear_screen()?;
+                redraw = true;
+            } else {
+                display.draw_race_size_warning(width, height)?;
+            }
+        }
+        
+        match input_handler.poll_race_input() {
+            (_, InputAction::QuitNow) => return Ok(None),
+            (_, InputAction::Quit) => {
+                if confirm_prompt(display, "Abandon the race?", "Yes, quit the race", "No, keep racing")? {
+                    return Ok(None);
+                }
+                drawn_size = None;
+            }
+            (Some(player), mut action) if fits => {
+                if let Some(question) = recycle_question(&race.boards[player], action, &display.layout, confirm_recycle) {
+                    if !confirm_prompt(display, &question, display.strings.yes_recycle, display.strings.keep_playing)? {
+                        action = InputAction::None;
+                    }
+                    drawn_size = None;
+                }
+                let board = &mut race.boards[player];
+                let old_moves = board.move_count;
+                board.move_rejected = false;
+                handle_game_action(board, action, &display.layout);
+                // A successful move replaces any complaint about the previous attempt
+                if board.move_count > old_moves && !board.move_rejected {
+                    board.status_message = None;
+                }
+                redraw = true;
+            }
+            _ => {}
+        }
+        
+        if let Some(player) = race.winner() {
+            display.draw_race_winner(player)?;
+            thread::sleep(Duration::from_secs(3));
+            return Ok(Some(player));
+        }
+        
+        if !fits {
+            thread::sleep(Duration::from_millis(10));
+            continue;
+        }
+        
+        for board in &mut race.boards {
+            let expired = board.status_message.as_ref()
+                .is_some_and(|(_, shown)| shown.elapsed() >= Duration::from_secs(STATUS_MESSAGE_SECS));
+            if expired {
+                board.status_message = None;
+                redraw = true;
+            }
+        }
+        
+        if redraw || shown_secs != race.boards[0].elapsed_secs() {
+            display.draw_race(&race)?;
+            shown_secs = race.boards[0].elapsed_secs();
+        }
+        
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+// The question to ask before an action turns the waste back over, if it needs one.
+// Recycling costs points, so players who asked are warned before the waste turns over.
+// Starting the last allowed pass can't be taken back by playing on, so that is always asked.
+fn recycle_question(game: &GameState, action: InputAction, layout: &Layout, confirm_recycle: bool) -> Option<String> {
+    // Enter on the stock draws just as S does
+    let action = match action {
+        InputAction::ActivateCursor => cursor_action(game),
+        action => action,
+    };
+    let draws = match action {
+        InputAction::DrawFromStock => true,
+        InputAction::MouseClick(x, y) => {
+            matches!(convert_mouse_to_game_position(x, y, game, layout), Some((PileType::Stock, _, _)))
+        }
+        _ => false,
+    };
+    if !draws || !game.draw_recycles() {
+        return None;
+    }
+    let penalty = game.rules.recycle_penalty();
+    if game.recycle_starts_final_pass() {
+        Some(game.strings.final_pass_question.to_string())
+    } else if confirm_recycle && penalty > 0 {
+        Some(format!("{} -{} {}", game.strings.recycle_for, penalty, game.strings.points_question))
+    } else {
+        None
+    }
+}
+
+fn load_replay(path: &str, rules: RulesConfig) -> Result<(Replay, Vec<GameState>), String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let replay = Replay::parse(&text)?;
+    let boards = replay.boards(rules)?;
+    Ok((replay, boards))
+}
+
+// Steps through a saved game one move at a time: right and left go forward and
+// back, up and down jump to the start and the end. Every board was built when
+// the file was loaded, so going back just shows the one from before.
+fn run_replay(replay: &Replay, boards: &[GameState], display: &mut Display) -> Result<(), Box<dyn std::error::Error>> {
+    let last = boards.len() - 1;
+    let mut shown = 0;
+    display.tutorial_prompt = Some("Replay: ← → step through the moves, ↑ ↓ jump to the start or end, Q to quit");
+    display.clear_screen()?;
+    
+    loop {
+        let mut board = boards[shown].clone();
+        let line = match shown {
+            0 => format!("Start of the game, seed {} ({} moves)", replay.seed, last),
+            n => format!("Move {} of {}: {}", n, last, describe_step(&replay.steps[n - 1], &boards[n - 1])),
+        };
+        board.show_status(line);
+        display.draw_game(&board)?;
+        
+        let key = match event::read()? {
+            Event::Key(key) => key,
+            _ => continue, // Anything else, such as a resize, just draws the board again
+        };
+        shown = match key.code {
+            KeyCode::Right => (shown + 1).min(last),
+            KeyCode::Left => shown.saturating_sub(1),
+            KeyCode::Up => 0,
+            KeyCode::Down => last,
+            KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc => return Ok(()),
+            _ => shown,
+        };
+    }
+}
+
+// Restores the terminal when dropped, whether main finishes normally or bails out with an error
+struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
+// Undoes raw mode, mouse capture and the hidden cursor. Only acts while raw mode is on,
+// so an early panic leaves the terminal alone and a second call does nothing.
+fn restore_terminal() {
+    if terminal::is_raw_mode_enabled().unwrap_or(false) {
+        let _ = execute!(
+            stdout(),
+            DisableMouseCapture,
+            Show,
+            EnableLineWrap,
+            ResetColor,
+            Clear(ClearType::All),
+            MoveTo(0, 0)
+        );
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn reset_stats() -> Result<(), Box<dyn std::error::Error>> {
+    let stats = Stats::load(STATS_PATH);
+    println!("This will permanently erase your lifetime statistics:");
+    println!("  {} games played, {} won, longest streak {}", stats.games_played, stats.games_won, stats.longest_streak);
+    println!("Saved games and settings are not affected.");
+    print!("Type 'reset' to confirm: ");
+    stdout().flush()?;
+    
+    let mut answer = String::new();
+    stdin().read_line(&mut answer)?;
+    if answer.trim() == "reset" {
+        Stats::default().save(STATS_PATH)?;
+        println!("Statistics reset.");
+    } else {
+        println!("Nothing changed.");
+    }
+    
+    Ok(())
+}
+
+fn show_welcome_screen(display: &Display) -> Result<(), Box<dyn std::error::Error>> {
+    let ascii = display.ascii;
     execute!(
         stdout(),
         Clear(ClearType::All),
@@ -134,41 +1097,45 @@ fn show_welcome_screen() -> Result<(), Box<dyn std::error::Error>> {
         MoveTo(0, 0)
     )?;
     
-    let lines = vec![
-        "",
-        "     ███╗   ██╗███████╗ ██████╗ ███╗   ██╗",
-        "     ████╗  ██║██╔════╝██╔═══██╗████╗  ██║",
-        "     ██╔██╗ ██║█████╗  ██║   ██║██╔██╗ ██║",
-        "     ██║╚██╗██║██╔══╝  ██║   ██║██║╚██╗██║",
-        "     ██║ ╚████║███████╗╚██████╔╝██║ ╚████║",
-        "     ╚═╝  ╚═══╝╚══════╝ ╚═════╝ ╚═╝  ╚═══╝",
-        "",
-        "         ███████╗ ██████╗ ██╗     ██╗████████╗",
-        "         ██╔════╝██╔═══██╗██║     ██║╚══██╔══╝",
-        "         ███████╗██║   ██║██║     ██║   ██║",
-        "         ╚════██║██║   ██║██║     ██║   ██║",
-        "         ███████║╚██████╔╝███████╗██║   ██║",
-        "         ╚══════╝ ╚═════╝ ╚══════╝╚═╝   ╚═╝",
-        "",
-        "                  ♠ ♥ ♦ ♣",
-        "",
-        "              === HOW TO PLAY ===",
-        "",
-        "   • Build foundations from Ace to King by suit",
-        "   • Stack tableau cards in descending order",
-        "   • Alternate colors (red on black, black on red)",
-        "   • Click cards to select, click again to move",
-        "   • Press SPACE to draw cards",
-        "   • Press A for auto-move",
-        "",
-        "              Press any key to start...",
-    ];
+    // The block-letter logo needs Unicode, so ASCII mode gets a plain title
+    let logo = if ascii {
+        vec![
+            "",
+            "          N E O N   S O L I T A I R E",
+            "",
+            "                  S  H  D  C",
+        ]
+    } else {
+        vec![
+            "",
+            "     ███╗   ██╗███████╗ ██████╗ ███╗   ██╗",
+            "     ████╗  ██║██╔════╝██╔═══██╗████╗  ██║",
+            "     ██╔██╗ ██║█████╗  ██║   ██║██╔██╗ ██║",
+            "     ██║╚██╗██║██╔══╝  ██║   ██║██║╚██╗██║",
+            "     ██║ ╚████║███████╗╚██████╔╝██║ ╚████║",
+            "     ╚═╝  ╚═══╝╚══════╝ ╚═════╝ ╚═╝  ╚═══╝",
+            "",
+            "         ███████╗ ██████╗ ██╗     ██╗████████╗",
+            "         ██╔════╝██╔═══██╗██║     ██║╚══██╔══╝",
+            "         ███████╗██║   ██║██║     ██║   ██║",
+            "         ╚════██║██║   ██║██║     ██║   ██║",
+            "         ███████║╚██████╔╝███████╗██║   ██║",
+            "         ╚══════╝ ╚═════╝ ╚══════╝╚═╝   ╚═╝",
+            "",
+            "                  ♠ ♥ ♦ ♣",
+        ]
+    };
+    let lines: Vec<String> = logo
+        .iter()
+        .chain(display.strings.welcome.iter())
+        .map(|line| if ascii { line.replace('•', "-") } else { line.to_string() })
+        .collect();
     
     for (i, line) in lines.iter().enumerate() {
         execute!(
             stdout(),
             MoveTo(10, 3 + i as u16),
-            SetForegroundColor(Color::Rgb { r: 255, g: 50, b: 255 }),
+            display.fg(Color::Rgb { r: 255, g: 50, b: 255 }),
             Print(line),
             ResetColor
         )?;
@@ -177,44 +1144,77 @@ fn show_welcome_screen() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn wait_for_keypress() -> Result<(), Box<dyn std::error::Error>> {
+fn wait_for_keypress() -> Result<KeyCode, Box<dyn std::error::Error>> {
     loop {
         if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(_) = event::read()? {
+            if let Event::Key(key) = event::read()? {
                 execute!(
                     stdout(),
                     Clear(ClearType::All),
                     MoveTo(0, 0)
                 )?;
-                break;
+                return Ok(key.code);
             }
         }
     }
-    Ok(())
 }
 
-fn confirm_quit() -> Result<bool, Box<dyn std::error::Error>> {
+// Asks a yes/no question full-screen; Esc counts as no
+fn confirm_prompt(display: &Display, question: &str, yes: &str, no: &str) -> Result<bool, Box<dyn std::error::Error>> {
     execute!(
         stdout(),
         Clear(ClearType::All),
         MoveTo(20, 10),
-        SetForegroundColor(Color::Rgb { r: 255, g: 200, b: 100 }),
-        Print("Are you sure you want to quit?"),
+        display.fg(Color::Rgb { r: 255, g: 200, b: 100 }),
+        Print(question),
         MoveTo(20, 12),
-        Print("[Y] Yes, quit the game"),
+        Print(format!("[Y] {}", yes)),
         MoveTo(20, 13),
-        Print("[N] No, keep playing"),
+        Print(format!("[N] {}", no)),
         ResetColor
     )?;
     
     loop {
-        if let Ok(event) = event::read() {
-            if let Event::Key(key) = event {
-                match key.code {
-                    KeyCode::Char('y') | KeyCode::Char('Y') => return Ok(true),
-                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => return Ok(false),
-                    _ => {}
-                }
+        if let Ok(Event::Key(key)) = event::read() {
+            match key.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => return Ok(true),
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => return Ok(false),
+                _ => {}
+            }
+        }
+    }
+}
+
+// How to finish once auto-complete is possible: card by card on screen, or everything at once
+enum Finish {
+    Animated,
+    Instant,
+}
+
+// Like confirm_prompt, with a second way to say yes. Declining gives None.
+fn finish_prompt(display: &Display, question: &str, no: &str) -> Result<Option<Finish>, Box<dyn std::error::Error>> {
+    execute!(
+        stdout(),
+        Clear(ClearType::All),
+        MoveTo(20, 10),
+        display.fg(Color::Rgb { r: 255, g: 200, b: 100 }),
+        Print(question),
+        MoveTo(20, 12),
+        Print(format!("[Y] {}", display.strings.finish_animated)),
+        MoveTo(20, 13),
+        Print(format!("[I] {}", display.strings.finish_instant)),
+        MoveTo(20, 14),
+        Print(format!("[N] {}", no)),
+        ResetColor
+    )?;
+    
+    loop {
+        if let Ok(Event::Key(key)) = event::read() {
+            match key.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => return Ok(Some(Finish::Animated)),
+                KeyCode::Char('i') | KeyCode::Char('I') => return Ok(Some(Finish::Instant)),
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => return Ok(None),
+                _ => {}
             }
         }
     }
@@ -225,16 +1225,54 @@ fn show_final_stats(game: &GameState) {
     println!("         GAME STATISTICS");
     println!("════════════════════════════════════════");
     println!(" Final Score: {}", game.score);
-    println!(" Total Moves: {}", game.move_count);
-    println!(" Status: {}", if game.is_won() { "🏆 VICTORY!" } else { "Game Ended" });
+    match game.rules.max_moves {
+        Some(max) => println!(" Total Moves: {} of {}", game.move_count, max),
+        None => println!(" Total Moves: {}", game.move_count),
+    }
+    println!(" Hints Used: {}", game.hints_used);
+    let reason = game.game_over_reason();
+    if let Some(par) = game.par {
+        // Only a win is rated, since stopping early would always come in under par
+        let rating = match game.move_count.cmp(&par) {
+            _ if reason != GameOverReason::Won => String::new(),
+            Ordering::Less => format!(" - {} under par!", par - game.move_count),
+            Ordering::Equal => " - right on par".to_string(),
+            Ordering::Greater => format!(" - +{} over par", game.move_count - par),
+        };
+        println!(" Par: {} moves{}", par, rating);
+    }
+    let status = match reason {
+        GameOverReason::Won => "🏆 VICTORY!",
+        GameOverReason::MoveLimit => "You reached the move limit",
+        GameOverReason::Stuck => "You ran out of moves",
+        GameOverReason::Quit => "You quit",
+    };
+    println!(" Status: {}", status);
     println!("════════════════════════════════════════");
     println!("\nThanks for playing Neon Solitaire!");
     
-    if !game.is_won() {
+    let tips: &[&str] = match reason {
+        GameOverReason::Won => &[],
+        GameOverReason::MoveLimit => &[
+            "Every move counts - avoid shuffling cards back and forth",
+            "Send cards to the foundations as soon as it's safe (G)",
+            "Plan a few moves ahead before drawing",
+        ],
+        GameOverReason::Stuck => &[
+            "Try to uncover face-down cards early",
+            "Empty columns are valuable - save them for Kings",
+            "Use Undo (Z) to back out and try a different line",
+        ],
+        GameOverReason::Quit => &[
+            "There were still moves to play - press H for hints when unsure",
+            "Use Undo (Z) to try different strategies",
+            "Press P to pause instead of quitting",
+        ],
+    };
+    if !tips.is_empty() {
         println!("\n💡 Tips for next time:");
-        println!("  • Try to uncover face-down cards early");
-        println!("  • Empty columns are valuable - save them for Kings");
-        println!("  • Use Undo (Z) to try different strategies");
-        println!("  • Press H for hints when stuck");
+        for tip in tips {
+            println!("  • {}", tip);
+        }
     }
 }
\ No newline at end of file
@@ -1,5 +1,7 @@
 use crate::card::Card;
-use crate::game::{GameState, PileType};
+use crate::game::{GameState, PileType, UndoKind};
+use crate::i18n::{Strings, ENGLISH};
+use crate::replay::Step;
 
 #[derive(Debug, Clone)]
 pub struct Move {
@@ -29,12 +31,18 @@ impl Move {
     }
 
     pub fn execute(&mut self, game: &mut GameState) -> bool {
+        self.execute_as(game, UndoKind::Move)
+    }
+
+    // Like execute, with the undo snapshot filed under the given kind
+    pub fn execute_as(&mut self, game: &mut GameState, kind: UndoKind) -> bool {
         // Validate the move first
         if !self.is_valid(game) {
             return false;
         }
 
-        game.save_undo_state();
+        game.save_undo_entry(kind);
+        game.record_step(Step::Move(self.from.clone(), self.to.clone()));
 
         // Remove cards from source
         let cards_to_move = match self.from.pile_type {
@@ -67,37 +75,82 @@ impl Move {
                 for card in cards_to_move {
                     game.tableau[self.to.pile_index].push(card);
                 }
-                self.score_change = 5;
+                // A card taken back down gives up what it earned going up, so it can't be farmed
+                self.score_change = if self.from.pile_type == PileType::Foundation {
+                    -game.rules.foundation_points()
+                } else {
+                    game.rules.tableau_points()
+                };
+                game.combo = 0;
             }
             PileType::Foundation => {
                 for card in cards_to_move {
                     game.foundations[self.to.pile_index].push(card);
                 }
-                self.score_change = 10;
+                self.score_change = game.foundation_move_points();
             }
             _ => return false,
         }
 
-        // Flip card if needed
-        if let Some((col, _)) = self.flipped_card {
+        // Flip card if needed; without auto-flip the player turns it over later
+        if let Some((col, _)) = self.flipped_card.filter(|_| game.rules.auto_flip) {
             if let Some(card) = game.tableau[col].last_mut() {
                 card.face_up = true;
-                self.score_change += 5;
+                self.score_change += game.rules.flip_points();
             }
         }
 
-        game.score += self.score_change;
+        game.add_score_at(self.score_change, self.to.pile_type, self.to.pile_index);
         game.move_count += 1;
+        game.move_made((self.to.pile_type == PileType::Foundation).then_some(self.to.pile_index));
 
         true
     }
 
+    pub fn describe(&self) -> String {
+        self.describe_with(&ENGLISH)
+    }
+
+    pub fn describe_with(&self, strings: &Strings) -> String {
+        let card = match self.cards.first() {
+            Some(card) if self.from.pile_type != PileType::Stock => card,
+            _ => return strings.draw_from_stock.to_string(),
+        };
+
+        let from = match self.from.pile_type {
+            PileType::Tableau => format!("{} {}", strings.from_column, self.from.pile_index + 1),
+            PileType::Foundation => strings.from_foundation.to_string(),
+            _ => strings.from_waste.to_string(),
+        };
+        let to = match self.to.pile_type {
+            PileType::Tableau => format!("{} {}", strings.to_column, self.to.pile_index + 1),
+            _ => strings.to_foundation.to_string(),
+        };
+
+        format!("{} {} {} {}", strings.move_card, card, from, to)
+    }
+
+    // Whether the cards would land back where they started, which is never a move
+    pub fn is_no_op(&self) -> bool {
+        self.from.pile_type == self.to.pile_type && self.from.pile_index == self.to.pile_index
+    }
+
     pub fn is_valid(&self, game: &GameState) -> bool {
+        // Dropping cards back on their own pile must not count as a move or score
+        if self.is_no_op() {
+            return false;
+        }
+        
+        // Strict rules keep every card that has gone up where it is
+        if self.from.pile_type == PileType::Foundation && !game.rules.allow_foundation_to_tableau {
+            return false;
+        }
+
         // Check source has cards
         let source_cards = match self.from.pile_type {
             PileType::Tableau => {
                 let col = self.from.pile_index;
-                if col >= 7 || self.from.card_index >= game.tableau[col].len() {
+                if col >= game.rules.tableau_columns || self.from.card_index >= game.tableau[col].len() {
                     return false;
                 }
                 &game.tableau[col][self.from.card_index..]
@@ -109,7 +162,7 @@ impl Move {
                 std::slice::from_ref(game.waste.last().unwrap())
             }
             PileType::Foundation => {
-                if self.from.pile_index >= 4 || game.foundations[self.from.pile_index].is_empty() {
+                if self.from.pile_index >= game.foundations.len() || game.foundations[self.from.pile_index].is_empty() {
                     return false;
                 }
                 std::slice::from_ref(game.foundations[self.from.pile_index].last().unwrap())
@@ -130,7 +183,7 @@ impl Move {
         match self.to.pile_type {
             PileType::Tableau => {
                 let col = self.to.pile_index;
-                if col >= 7 {
+                if col >= game.rules.tableau_columns {
                     return false;
                 }
                 game.is_valid_tableau_move(&source_cards[0], col)
@@ -140,7 +193,7 @@ impl Move {
                     return false;  // Can only move one card to foundation
                 }
                 let foundation_idx = self.to.pile_index;
-                if foundation_idx >= 4 {
+                if foundation_idx >= game.foundations.len() {
                     return false;
                 }
                 game.is_valid_foundation_move(&source_cards[0], foundation_idx)
@@ -162,7 +215,7 @@ pub fn find_valid_moves(game: &GameState) -> Vec<Move> {
         };
 
         // Try each tableau column
-        for col in 0..7 {
+        for col in 0..game.rules.tableau_columns {
             let to = MoveLocation {
                 pile_type: PileType::Tableau,
                 pile_index: col,
@@ -175,7 +228,7 @@ pub fn find_valid_moves(game: &GameState) -> Vec<Move> {
         }
 
         // Try each foundation
-        for f in 0..4 {
+        for f in 0..game.foundations.len() {
             let to = MoveLocation {
                 pile_type: PileType::Foundation,
                 pile_index: f,
@@ -189,7 +242,7 @@ pub fn find_valid_moves(game: &GameState) -> Vec<Move> {
     }
 
     // Tableau to tableau/foundation
-    for from_col in 0..7 {
+    for from_col in 0..game.rules.tableau_columns {
         if game.tableau[from_col].is_empty() {
             continue;
         }
@@ -208,7 +261,7 @@ pub fn find_valid_moves(game: &GameState) -> Vec<Move> {
             };
 
             // Try moving to other tableau columns
-            for to_col in 0..7 {
+            for to_col in 0..game.rules.tableau_columns {
                 if from_col == to_col {
                     continue;
                 }
@@ -226,7 +279,7 @@ pub fn find_valid_moves(game: &GameState) -> Vec<Move> {
 
             // Try moving single cards to foundations
             if cards.len() == 1 {
-                for f in 0..4 {
+                for f in 0..game.foundations.len() {
                     let to = MoveLocation {
                         pile_type: PileType::Foundation,
                         pile_index: f,
@@ -244,106 +297,267 @@ pub fn find_valid_moves(game: &GameState) -> Vec<Move> {
     moves
 }
 
-pub fn find_best_move(game: &GameState) -> Option<Move> {
-    let moves = find_valid_moves(game);
-    
-    // Prioritize moves to foundation
-    for mv in &moves {
-        if mv.to.pile_type == PileType::Foundation {
-            return Some(mv.clone());
+pub fn send_to_foundation(game: &mut GameState, from_pile: PileType, pile_index: usize) -> bool {
+    // Only the top card of the waste or a tableau column can go up
+    let (card, card_index) = match from_pile {
+        PileType::Waste => match game.waste.last() {
+            Some(&card) => (card, game.waste.len() - 1),
+            None => return false,
+        },
+        PileType::Tableau => match game.tableau.get(pile_index).and_then(|col| col.last()) {
+            Some(&card) => (card, game.tableau[pile_index].len() - 1),
+            None => return false,
+        },
+        _ => return false,
+    };
+
+    let from = MoveLocation {
+        pile_type: from_pile,
+        pile_index,
+        card_index,
+    };
+
+    for f in 0..game.foundations.len() {
+        let to = MoveLocation {
+            pile_type: PileType::Foundation,
+            pile_index: f,
+            card_index: game.foundations[f].len(),
+        };
+        let mut mv = Move::new(from.clone(), to, vec![card]);
+        if mv.execute(game) {
+            return true;
         }
     }
+
+    false
+}
+
+// Lower is better: foundation moves, then moves that reveal a card,
+// then other tableau shuffles, then plays from the waste
+fn move_priority(game: &GameState, mv: &Move) -> u8 {
+    if mv.to.pile_type == PileType::Foundation {
+        0
+    } else if mv.from.pile_type == PileType::Tableau
+        && mv.from.card_index > 0
+        && !game.tableau[mv.from.pile_index][mv.from.card_index - 1].face_up
+    {
+        1
+    } else if mv.from.pile_type == PileType::Tableau && mv.to.pile_type == PileType::Tableau {
+        2
+    } else {
+        3
+    }
+}
+
+// A card can go up without ever being missed once the opposite-colour cards
+// one rank lower are all on the foundations, so nothing left could need it
+pub fn is_safe_for_foundation(game: &GameState, card: &Card) -> bool {
+    if card.rank as u8 <= 2 {
+        return true;
+    }
+    let covering = game.foundations.iter()
+        .filter_map(|f| f.last())
+        .filter(|top| top.is_red() != card.is_red() && top.rank as u8 >= card.rank as u8 - 1)
+        .count();
+    covering >= 2 * game.rules.deck_count()
+}
+
+// The first foundation move whose card is safe to send up, if any
+pub fn safe_foundation_move(game: &GameState) -> Option<Move> {
+    find_valid_moves(game).into_iter().find(|mv| {
+        mv.to.pile_type == PileType::Foundation
+            && mv.cards.first().is_some_and(|card| is_safe_for_foundation(game, card))
+    })
+}
+
+// Where the top waste card is best played: a foundation if one takes it, otherwise a column
+pub fn best_waste_move(game: &GameState) -> Option<Move> {
+    find_valid_moves(game)
+        .into_iter()
+        .filter(|mv| mv.from.pile_type == PileType::Waste)
+        .min_by_key(|mv| move_priority(game, mv))
+}
+
+// The waste card's move when it has exactly one place to go, and nothing to weigh up
+pub fn only_waste_move(game: &GameState) -> Option<Move> {
+    let mut moves = find_valid_moves(game).into_iter().filter(|mv| mv.from.pile_type == PileType::Waste);
+    match (moves.next(), moves.next()) {
+        (Some(only), None) => Some(only),
+        _ => None,
+    }
+}
+
+pub fn find_best_move(game: &GameState) -> Option<Move> {
+    find_valid_moves(game)
+        .into_iter()
+        .min_by_key(|mv| move_priority(game, mv))
+}
+
+// Every move worth suggesting, best first. Shifting a whole column onto an
+// empty one is legal but never helps, so it is left out.
+pub fn hint_moves(game: &GameState) -> Vec<Move> {
+    let mut moves: Vec<Move> = find_valid_moves(game)
+        .into_iter()
+        .filter(|mv| {
+            !(mv.from.pile_type == PileType::Tableau
+                && mv.from.card_index == 0
+                && mv.to.pile_type == PileType::Tableau
+                && game.tableau[mv.to.pile_index].is_empty())
+        })
+        .collect();
+    moves.sort_by_key(|mv| move_priority(game, mv));
+    moves
+}
+
+// Nothing left to try: no move on the board, and no card the stock or waste
+// could still turn up fits anywhere, however often it is drawn
+pub fn is_stuck(game: &GameState) -> bool {
+    if !hint_moves(game).is_empty() || game.face_down_top().is_some() {
+        return false;
+    }
+    // Cards buried in the waste only come round again if it can be recycled
+    let waste = if game.can_recycle() { &game.waste[..] } else { &game.waste[game.waste.len().saturating_sub(1)..] };
+    !game.stock.iter().chain(waste).any(|card| {
+        (0..game.rules.tableau_columns).any(|col| game.is_valid_tableau_move(card, col))
+            || (0..game.foundations.len()).any(|idx| game.is_valid_foundation_move(card, idx))
+    })
+}
+
+// The move that turned one position into the next, worked out from which pile
+// lost cards and which gained them. A draw is stock to waste, a recycle the reverse.
+pub fn move_between(before: &GameState, after: &GameState) -> Option<Move> {
+    let piles = |game: &GameState| -> Vec<(PileType, usize, usize)> {
+        [(PileType::Stock, 0, game.stock.len()), (PileType::Waste, 0, game.waste.len())]
+            .into_iter()
+            .chain(game.tableau.iter().enumerate().map(|(i, pile)| (PileType::Tableau, i, pile.len())))
+            .chain(game.foundations.iter().enumerate().map(|(i, pile)| (PileType::Foundation, i, pile.len())))
+            .collect()
+    };
+    let (was, now) = (piles(before), piles(after));
+    let changed = |shrank: bool| {
+        was.iter().zip(&now).find(|(w, n)| if shrank { n.2 < w.2 } else { n.2 > w.2 }).map(|(w, n)| (*w, *n))
+    };
+    let ((from_type, from_index, _), (_, _, from_left)) = changed(true)?;
+    let ((to_type, to_index, to_had), _) = changed(false)?;
+    let cards = match to_type {
+        PileType::Stock => &after.stock[to_had..],
+        PileType::Waste => &after.waste[to_had..],
+        PileType::Tableau => &after.tableau[to_index][to_had..],
+        PileType::Foundation => &after.foundations[to_index][to_had..],
+    };
+    Some(Move::new(
+        MoveLocation { pile_type: from_type, pile_index: from_index, card_index: from_left },
+        MoveLocation { pile_type: to_type, pile_index: to_index, card_index: to_had },
+        cards.to_vec(),
+    ))
+}
+
+// A pointer for players who haven't spotted that only the stock can help:
+// nothing on the board or waste moves, but drawing could still turn up a card that does
+pub fn draw_nudge(game: &GameState) -> Option<&'static str> {
+    if !hint_moves(game).is_empty() || !game.can_draw() || is_stuck(game) {
+        return None;
+    }
+    Some(if game.draw_recycles() { game.strings.nudge_recycle } else { game.strings.nudge_draw })
+}
+
+// Chance, from what the player can see, that the next draw turns up at least one
+// card that would play at once onto a foundation or a column. Every card not yet
+// seen (the stock and the face-down tableau cards) is taken as equally likely to
+// be in any of the places drawn from. None when the next draw recycles or
+// nothing is left unseen.
+pub fn draw_odds(game: &GameState) -> Option<f64> {
+    if game.stock.is_empty() {
+        return None;
+    }
+    let unseen: Vec<&Card> = game.stock.iter()
+        .chain(game.tableau.iter().flatten().filter(|card| !card.face_up))
+        .collect();
+    let playable = |card: &Card| {
+        (0..game.foundations.len()).any(|f| game.is_valid_foundation_move(card, f))
+            || (0..game.rules.tableau_columns).any(|col| game.is_valid_tableau_move(card, col))
+    };
+    let total = unseen.len();
+    let needed = unseen.iter().filter(|card| playable(card)).count();
     
-    // Then moves that reveal cards
-    for mv in &moves {
-        if mv.from.pile_type == PileType::Tableau {
-            let col = mv.from.pile_index;
-            if mv.from.card_index > 0 && !game.tableau[col][mv.from.card_index - 1].face_up {
-                return Some(mv.clone());
-            }
-        }
+    // One minus the chance every drawn card misses, drawing without replacement
+    let drawn = game.cards_per_draw().min(game.stock.len());
+    let all_miss: f64 = (0..drawn)
+        .map(|i| (total - needed).saturating_sub(i) as f64 / (total - i) as f64)
+        .product();
+    Some(1.0 - all_miss)
+}
+
+// Shows the next suggestion each time it is called, falling back to drawing
+pub fn cycle_hint(game: &mut GameState) {
+    // A card waiting to be turned over comes before anything else, and is free
+    if let Some(col) = game.face_down_top() {
+        game.hint = None;
+        game.show_status(format!("{} {}: {} {}", game.strings.turn_over_column, col + 1, game.strings.press, col + 1));
+        return;
     }
     
-    // Then any tableau to tableau move
-    for mv in &moves {
-        if mv.from.pile_type == PileType::Tableau && mv.to.pile_type == PileType::Tableau {
-            return Some(mv.clone());
-        }
+    let mut candidates = hint_moves(game);
+    if candidates.is_empty() && game.can_draw() {
+        let stock = MoveLocation {
+            pile_type: PileType::Stock,
+            pile_index: 0,
+            card_index: game.stock.len(),
+        };
+        let waste = MoveLocation {
+            pile_type: PileType::Waste,
+            pile_index: 0,
+            card_index: game.waste.len(),
+        };
+        candidates.push(Move::new(stock, waste, Vec::new()));
     }
+
+    // Nothing to move and nothing to draw: say so rather than show nothing
+    if candidates.is_empty() {
+        game.hint = None;
+        game.show_status(game.strings.no_legal_moves.to_string());
+        return;
+    }
+
+    let index = game.hint_cycle % candidates.len();
+    game.hint = Some(candidates.swap_remove(index));
+    game.hint_cycle += 1;
+    game.record_hint();
+}
+
+// One foundation move toward finishing the game, tableau columns first and then the waste.
+// Returns false once nothing more can go up. A run of steps shares one undo entry.
+pub fn auto_complete_step(game: &mut GameState) -> bool {
+    // Face-up column tops first, then the waste, each to the first foundation that takes it
+    let sources = (0..game.rules.tableau_columns)
+        .filter_map(|col| {
+            let card = game.tableau[col].last().filter(|card| card.face_up)?;
+            Some((MoveLocation { pile_type: PileType::Tableau, pile_index: col, card_index: game.tableau[col].len() - 1 }, *card))
+        })
+        .chain(game.waste.last().map(|card| {
+            (MoveLocation { pile_type: PileType::Waste, pile_index: 0, card_index: game.waste.len() - 1 }, *card)
+        }))
+        .collect::<Vec<_>>();
     
-    // Finally, waste to tableau
-    for mv in &moves {
-        if mv.from.pile_type == PileType::Waste {
-            return Some(mv.clone());
+    for (from, card) in sources {
+        for f in 0..game.foundations.len() {
+            if game.is_valid_foundation_move(&card, f) {
+                let to = MoveLocation { pile_type: PileType::Foundation, pile_index: f, card_index: game.foundations[f].len() };
+                // The whole run shares one undo step, and a card it uncovers scores as any flip does
+                return Move::new(from, to, vec![card]).execute_as(game, UndoKind::AutoComplete);
+            }
         }
     }
     
-    None
+    false
 }
 
+// Every remaining foundation move at once, in the same order auto_complete_step takes them.
+// Each step takes a card off the board, so this always ends.
 pub fn auto_complete(game: &mut GameState) -> bool {
     let mut moves_made = false;
-    let mut attempts = 0;
-    const MAX_ATTEMPTS: u32 = 100;
-    
-    while attempts < MAX_ATTEMPTS {
-        let mut made_move = false;
-        
-        // Try to move any card to foundation
-        for col in 0..7 {
-            if !game.tableau[col].is_empty() {
-                if let Some(card) = game.tableau[col].last() {
-                    if card.face_up {
-                        for f in 0..4 {
-                            if game.is_valid_foundation_move(card, f) {
-                                game.save_undo_state();
-                                let card = game.tableau[col].pop().unwrap();
-                                game.foundations[f].push(card);
-                                
-                                // Flip new top card if needed
-                                if let Some(new_top) = game.tableau[col].last_mut() {
-                                    if !new_top.face_up {
-                                        new_top.face_up = true;
-                                    }
-                                }
-                                
-                                game.score += 10;
-                                game.move_count += 1;
-                                made_move = true;
-                                moves_made = true;
-                                break;
-                            }
-                        }
-                    }
-                }
-                if made_move { break; }
-            }
-        }
-        
-        // Try waste pile
-        if !made_move && !game.waste.is_empty() {
-            if let Some(card) = game.waste.last() {
-                for f in 0..4 {
-                    if game.is_valid_foundation_move(card, f) {
-                        game.save_undo_state();
-                        let card = game.waste.pop().unwrap();
-                        game.foundations[f].push(card);
-                        game.score += 10;
-                        game.move_count += 1;
-                        made_move = true;
-                        moves_made = true;
-                        break;
-                    }
-                }
-            }
-        }
-        
-        if !made_move {
-            break;
-        }
-        
-        attempts += 1;
+    while auto_complete_step(game) {
+        moves_made = true;
     }
-    
     moves_made
 }
\ No newline at end of file
@@ -0,0 +1,33 @@
+use crate::game::GameState;
+
+// Two players race through the same deal side by side on one keyboard
+pub const PLAYERS: usize = 2;
+
+// Columns given to each board; the second board starts this far right
+pub const BOARD_WIDTH: u16 = 80;
+
+// Smallest terminal both boards and their status lines fit in
+pub const MIN_WIDTH: u16 = BOARD_WIDTH * PLAYERS as u16;
+pub const MIN_HEIGHT: u16 = 30;
+
+pub struct Race {
+    pub boards: [GameState; PLAYERS],
+}
+
+impl Race {
+    // Both players get an identical copy of the deal
+    pub fn new(game: GameState) -> Self {
+        Race {
+            boards: [game.clone(), game],
+        }
+    }
+
+    // The first player to finish their board, if anyone has
+    pub fn winner(&self) -> Option<usize> {
+        self.boards.iter().position(|board| board.is_won())
+    }
+
+    pub fn fits(width: u16, height: u16) -> bool {
+        width >= MIN_WIDTH && height >= MIN_HEIGHT
+    }
+}
@@ -0,0 +1,194 @@
+use crate::card::Card;
+use crate::game::{GameState, PileType};
+use crate::moves::{Move, MoveLocation};
+use crate::rules::RulesConfig;
+
+// First line of every replay file, so anything else is turned away
+pub const REPLAY_HEADER: &str = "Neon Solitaire replay";
+
+// One thing the player did that changed the board. Undone steps are dropped,
+// so a game's history is always the line of play that led to the board shown.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step {
+    Draw(usize),                     // Cards asked for; a recycle when the stock was empty
+    Flip(usize),                     // A face-down top card turned over by hand
+    Move(MoveLocation, MoveLocation), // Cards from one pile to another, as Move::execute takes them
+}
+
+// A game written out as where it started and every step from there. The
+// opening is a position string, so dealt games, endgames and loaded positions
+// all replay the same way.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub auto_flip: bool, // Without it the file holds a Flip step for every card turned over
+    pub opening: String,
+    pub steps: Vec<Step>,
+}
+
+impl Replay {
+    // The game so far; with nothing played yet it is just the opening
+    pub fn of(game: &GameState) -> Self {
+        Replay {
+            seed: game.seed,
+            auto_flip: game.rules.auto_flip,
+            opening: game.opening.clone().unwrap_or_else(|| game.to_position_string()),
+            steps: game.history.clone(),
+        }
+    }
+
+    // The header, then "seed N", "manual-flip" if it applies, "start <position>"
+    // and a line per step: "D3" draws three, "U4" turns over column 4's top card,
+    // "T3:5>T6" moves column 3 from its fifth card onto column 6, and "W>F1" or
+    // "F2>T7" move the top card of the waste or a foundation
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{}\nseed {}\n", REPLAY_HEADER, self.seed);
+        if !self.auto_flip {
+            text.push_str("manual-flip\n");
+        }
+        text.push_str(&format!("start {}\n", self.opening));
+        for step in &self.steps {
+            let line = match step {
+                Step::Draw(count) => format!("D{}", count),
+                Step::Flip(col) => format!("U{}", col + 1),
+                Step::Move(from, to) => format!("{}>{}", source_code(from), pile_code(to.pile_type, to.pile_index)),
+            };
+            text.push_str(&line);
+            text.push('\n');
+        }
+        text
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+        if lines.next() != Some(REPLAY_HEADER) {
+            return Err("Not a replay file".to_string());
+        }
+        let mut replay = Replay { seed: 0, auto_flip: true, opening: String::new(), steps: Vec::new() };
+        for line in lines {
+            if let Some(seed) = line.strip_prefix("seed ") {
+                replay.seed = seed.parse().map_err(|_| format!("Invalid seed: {}", seed))?;
+            } else if line == "manual-flip" {
+                replay.auto_flip = false;
+            } else if let Some(position) = line.strip_prefix("start ") {
+                replay.opening = position.to_string();
+            } else {
+                replay.steps.push(parse_step(line).ok_or_else(|| format!("Invalid step: {}", line))?);
+            }
+        }
+        if replay.opening.is_empty() {
+            return Err("The replay has no start position".to_string());
+        }
+        Ok(replay)
+    }
+
+    // Rebuilds the game: the board before any step, then after each one.
+    // A step that doesn't fit the board it lands on stops the replay.
+    pub fn boards(&self, rules: RulesConfig) -> Result<Vec<GameState>, String> {
+        let rules = RulesConfig { auto_flip: self.auto_flip, ..rules };
+        let mut game = GameState::from_position_string(&self.opening, rules)?;
+        game.seed = self.seed;
+        let mut boards = vec![game.clone()];
+        for (i, step) in self.steps.iter().enumerate() {
+            let applied = match step {
+                Step::Draw(count) => {
+                    let moves = game.move_count;
+                    game.draw_cards(*count);
+                    game.move_count > moves
+                }
+                Step::Flip(col) => *col < game.tableau.len() && game.flip_top(*col),
+                Step::Move(from, to) => resolve(&game, from, to).is_some_and(|mut mv| mv.execute(&mut game)),
+            };
+            if !applied {
+                return Err(format!("Step {} doesn't fit the board it is played on", i + 1));
+            }
+            // Each board is kept whole, so none needs its own undo snapshots
+            game.undo_stack.clear();
+            boards.push(game.clone());
+        }
+        Ok(boards)
+    }
+}
+
+// What a step did, worded for the viewer, given the board it was played on
+pub fn describe_step(step: &Step, before: &GameState) -> String {
+    match step {
+        Step::Draw(_) if before.stock.is_empty() => "Turn the waste back over".to_string(),
+        Step::Draw(count) => format!("Draw {} from the stock", count.min(&before.stock.len())),
+        Step::Flip(col) => format!("Turn over the top card of column {}", col + 1),
+        Step::Move(from, to) => resolve(before, from, to).map_or_else(|| "Move".to_string(), |mv| mv.describe()),
+    }
+}
+
+// The Move a step makes on this board. A file only says where a tableau run
+// starts, so waste and foundation moves take whatever card is on top.
+fn resolve(game: &GameState, from: &MoveLocation, to: &MoveLocation) -> Option<Move> {
+    let pile: &[Card] = match from.pile_type {
+        PileType::Tableau => game.tableau.get(from.pile_index)?,
+        PileType::Waste => &game.waste,
+        PileType::Foundation => game.foundations.get(from.pile_index)?,
+        PileType::Stock => return None,
+    };
+    let card_index = match from.pile_type {
+        PileType::Tableau => from.card_index,
+        _ => pile.len().checked_sub(1)?,
+    };
+    let cards = pile.get(card_index..).filter(|cards| !cards.is_empty())?.to_vec();
+    let to_len = match to.pile_type {
+        PileType::Tableau => game.tableau.get(to.pile_index)?.len(),
+        PileType::Foundation => game.foundations.get(to.pile_index)?.len(),
+        _ => return None,
+    };
+    let from = MoveLocation { card_index, ..from.clone() };
+    let to = MoveLocation { card_index: to_len, ..to.clone() };
+    Some(Move::new(from, to, cards))
+}
+
+fn pile_code(pile_type: PileType, index: usize) -> String {
+    match pile_type {
+        PileType::Tableau => format!("T{}", index + 1),
+        PileType::Foundation => format!("F{}", index + 1),
+        PileType::Waste => "W".to_string(),
+        PileType::Stock => "S".to_string(),
+    }
+}
+
+// Tableau moves say which card the run starts at; other piles only give up their top card
+fn source_code(from: &MoveLocation) -> String {
+    match from.pile_type {
+        PileType::Tableau => format!("T{}:{}", from.pile_index + 1, from.card_index + 1),
+        pile_type => pile_code(pile_type, from.pile_index),
+    }
+}
+
+fn parse_step(line: &str) -> Option<Step> {
+    if let Some(count) = line.strip_prefix('D') {
+        return count.parse().ok().map(Step::Draw);
+    }
+    if let Some(col) = line.strip_prefix('U') {
+        return col.parse::<usize>().ok()?.checked_sub(1).map(Step::Flip);
+    }
+    let (from, to) = line.split_once('>')?;
+    let (from, row) = match from.split_once(':') {
+        Some((pile, row)) => (pile, Some(row.parse::<usize>().ok()?.checked_sub(1)?)),
+        None => (from, None),
+    };
+    let (from_type, from_index) = parse_pile(from)?;
+    let (to_type, to_index) = parse_pile(to)?;
+    if (from_type == PileType::Tableau) != row.is_some() || to_type == PileType::Waste {
+        return None;
+    }
+    // Waste and foundation moves take the top card, which the board supplies on replay
+    let from = MoveLocation { pile_type: from_type, pile_index: from_index, card_index: row.unwrap_or(0) };
+    let to = MoveLocation { pile_type: to_type, pile_index: to_index, card_index: 0 };
+    Some(Step::Move(from, to))
+}
+
+fn parse_pile(code: &str) -> Option<(PileType, usize)> {
+    match code.split_at(code.len().min(1)) {
+        ("W", "") => Some((PileType::Waste, 0)),
+        ("T", index) => Some((PileType::Tableau, index.parse::<usize>().ok()?.checked_sub(1)?)),
+        ("F", index) => Some((PileType::Foundation, index.parse::<usize>().ok()?.checked_sub(1)?)),
+        _ => None,
+    }
+}
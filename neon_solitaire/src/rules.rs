@@ -0,0 +1,177 @@
+use crate::card::{Card, Rank, create_standard_deck};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScoringMode {
+    Standard, // Points for every useful move, small penalty for recycling
+    Vegas,    // Buy in for a point per card, win points back per foundation card
+}
+
+// Points for everything that scores, in one place so variants and house rules
+// can change them. Penalties are given as the points taken away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScoringConfig {
+    pub tableau: i32,         // A card or run moved onto a tableau column
+    pub foundation: i32,      // A card sent up to a foundation, before any combo
+    pub flip: i32,            // A face-down tableau card turned over
+    pub recycle_penalty: i32, // The waste turned back into the stock
+    pub hint_penalty: i32,    // A hint asked for
+    pub buy_in: i32,          // Owed per card in the deck before the first move
+}
+
+impl ScoringConfig {
+    pub const STANDARD: ScoringConfig = ScoringConfig {
+        tableau: 5,
+        foundation: 10,
+        flip: 5,
+        recycle_penalty: 20,
+        hint_penalty: 0,
+        buy_in: 0,
+    };
+
+    // Only foundation cards pay; limited passes are the price of recycling
+    pub const VEGAS: ScoringConfig = ScoringConfig {
+        tableau: 0,
+        foundation: 5,
+        flip: 0,
+        recycle_penalty: 0,
+        hint_penalty: 0,
+        buy_in: 1,
+    };
+}
+
+impl Default for ScoringConfig {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RulesConfig {
+    pub tableau_columns: usize,   // Column n is dealt n cards, so at most 9 fit in a deck
+    pub foundations: usize,       // One per suit per deck: 4 for a single deck, 8 for two
+    pub foundation_size: usize,   // Cards per completed foundation, i.e. the highest rank used
+    pub scoring: ScoringMode,
+    pub points: ScoringConfig,    // What each scoring event is worth; vegas() swaps in its own
+    pub max_passes: Option<u32>,  // Times through the stock, None for unlimited
+    pub max_moves: Option<u32>,   // Challenge mode: play ends once this many moves are made
+    pub combo: bool,              // Back-to-back foundation moves multiply their points
+    pub auto_flip: bool,          // Turn a column's new top card over at once; off, the player flips it
+    pub undo_limit: usize,        // Undo snapshots kept; 0 turns undo off
+    pub draw_all: bool,           // Easy mode: every draw turns the whole stock over
+    pub allow_foundation_to_tableau: bool, // Cards may come back down off the foundations; off for strict play
+}
+
+impl Default for RulesConfig {
+    fn default() -> Self {
+        RulesConfig {
+            tableau_columns: 7,
+            foundations: 4,
+            foundation_size: 13,
+            scoring: ScoringMode::Standard,
+            points: ScoringConfig::STANDARD,
+            max_passes: None,
+            max_moves: None,
+            combo: false,
+            auto_flip: true,
+            undo_limit: Self::UNDO_LIMIT,
+            draw_all: false,
+            allow_foundation_to_tableau: true,
+        }
+    }
+}
+
+impl RulesConfig {
+    pub const MAX_TABLEAU_COLUMNS: usize = 9;
+    pub const MAX_COMBO: u32 = 5;
+    pub const UNDO_LIMIT: usize = 100;
+
+    // Clamps values the deal can't honour
+    pub fn validated(mut self) -> Self {
+        self.foundations = (self.foundations.max(4) / 4) * 4; // Whole decks only
+        self.foundation_size = self.foundation_size.clamp(1, 13);
+        self.tableau_columns = self.tableau_columns.clamp(1, Self::MAX_TABLEAU_COLUMNS);
+        self.max_passes = self.max_passes.map(|passes| passes.max(1));
+        while self.tableau_cards() > self.deck_size() {
+            self.tableau_columns -= 1;
+        }
+        self
+    }
+
+    pub fn deck_count(&self) -> usize {
+        self.foundations / 4
+    }
+
+    pub fn deck_size(&self) -> usize {
+        self.foundations * self.foundation_size
+    }
+
+    pub fn highest_rank(&self) -> Rank {
+        Rank::from_value(self.foundation_size as u8).unwrap_or(Rank::King)
+    }
+
+    // Every deck in play, with ranks above the foundation size left out
+    pub fn create_deck(&self) -> Vec<Card> {
+        let mut deck = Vec::with_capacity(self.deck_size());
+        for _ in 0..self.deck_count() {
+            deck.extend(
+                create_standard_deck()
+                    .into_iter()
+                    .filter(|card| card.rank as usize <= self.foundation_size),
+            );
+        }
+        deck
+    }
+
+    pub fn tableau_cards(&self) -> usize {
+        self.tableau_columns * (self.tableau_columns + 1) / 2
+    }
+
+    // Vegas rules: one pass with draw 1, three with draw 3
+    pub fn vegas(mut self, draw_count: usize) -> Self {
+        self.scoring = ScoringMode::Vegas;
+        self.points = ScoringConfig::VEGAS;
+        self.max_passes = Some(if draw_count == 1 { 1 } else { 3 });
+        self
+    }
+
+    pub fn starting_score(&self) -> i32 {
+        -self.points.buy_in * self.deck_size() as i32
+    }
+
+    // Lowest the score can go: none in Vegas, where it starts below zero
+    pub fn score_floor(&self) -> Option<i32> {
+        match self.scoring {
+            ScoringMode::Standard => Some(0),
+            ScoringMode::Vegas => None,
+        }
+    }
+
+    pub fn tableau_points(&self) -> i32 {
+        self.points.tableau
+    }
+
+    pub fn foundation_points(&self) -> i32 {
+        self.points.foundation
+    }
+
+    // The nth foundation move in a row scores n times over, up to five times
+    pub fn combo_multiplier(&self, streak: u32) -> i32 {
+        if self.combo {
+            streak.clamp(1, Self::MAX_COMBO) as i32
+        } else {
+            1
+        }
+    }
+
+    pub fn flip_points(&self) -> i32 {
+        self.points.flip
+    }
+
+    pub fn hint_cost(&self) -> i32 {
+        self.points.hint_penalty
+    }
+
+    pub fn recycle_penalty(&self) -> i32 {
+        self.points.recycle_penalty
+    }
+}
@@ -0,0 +1,101 @@
+use crate::card::{CardBack, SuitPalette};
+use crate::i18n::Language;
+use crate::layout::Spacing;
+use crate::rules::RulesConfig;
+use crossterm::style::Color;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub bell: bool,               // Ring the terminal bell on game events
+    pub confirm_quit: bool,       // Ask before Q/Esc ends the game
+    pub mouse: bool,              // Capture the mouse for clicks; off leaves the terminal's text selection alone
+    pub card_back: CardBack,      // Face-down card artwork
+    pub animations: bool,         // Movement on screen, such as the pulsing selection
+    pub draw_animation_ms: u64,   // Pause between cards arriving on the waste; 0 for none
+    pub spacing: Spacing,         // Gap between tableau columns
+    pub language: Language,       // Language for the welcome screen, stats, controls and hints
+    pub recycle_penalty: Option<u32>, // Points lost for turning the waste over; 0 for none, unset for the usual 20
+    pub hint_penalty: u32,        // Points lost per hint in standard scoring (default 0, hints are free)
+    pub combo: bool,              // Foundation moves in a row score double, triple and so on
+    pub undo_limit: usize,        // Moves undo can take back; 0 turns undo off
+    pub allow_foundation_to_tableau: bool, // Cards may be taken back down off the foundations; false for strict play
+    pub suggest_waste_move: bool, // After a draw, highlight the waste card's move when there is only one
+    pub offer_auto_complete: bool, // Ask to finish the game as soon as auto-complete becomes possible
+    pub draw_odds: bool,          // Show the chance the next draw turns up a card that plays
+    pub score_ceiling: bool,      // Show the highest score still reachable in the HUD
+    pub waste_list: bool,         // List the whole waste beside the board, not just the top three
+    pub confirm_recycle: bool,    // Ask before a draw turns the waste over for a penalty
+    pub hud: bool,                // Start with the one-line status bar instead of the title and stats
+    pub suit_colors: BTreeMap<String, Value>, // e.g. "hearts": [255, 0, 0]; checked by suit_palette()
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            bell: false,
+            confirm_quit: true,
+            mouse: true,
+            card_back: CardBack::default(),
+            animations: true,
+            draw_animation_ms: 80,
+            spacing: Spacing::default(),
+            language: Language::default(),
+            recycle_penalty: None,
+            hint_penalty: 0,
+            combo: false,
+            undo_limit: RulesConfig::UNDO_LIMIT,
+            allow_foundation_to_tableau: true,
+            suggest_waste_move: false,
+            offer_auto_complete: false,
+            draw_odds: false,
+            score_ceiling: false,
+            waste_list: false,
+            confirm_recycle: false,
+            hud: false,
+            suit_colors: BTreeMap::new(),
+        }
+    }
+}
+
+impl Settings {
+    // Missing or malformed files fall back to defaults rather than stopping the game
+    pub fn load<P: AsRef<Path>>(path: P) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    // Neon defaults with any valid overrides applied. Entries that aren't a
+    // known suit with three 0-255 values are skipped, not treated as errors.
+    pub fn suit_palette(&self) -> SuitPalette {
+        let mut palette = SuitPalette::default();
+        for (name, value) in &self.suit_colors {
+            let Some(color) = parse_rgb(value) else {
+                continue;
+            };
+            match name.to_lowercase().as_str() {
+                "hearts" => palette.hearts = color,
+                "diamonds" => palette.diamonds = color,
+                "clubs" => palette.clubs = color,
+                "spades" => palette.spades = color,
+                _ => {}
+            }
+        }
+        palette
+    }
+}
+
+fn parse_rgb(value: &Value) -> Option<Color> {
+    let channels = value.as_array()?;
+    if channels.len() != 3 {
+        return None;
+    }
+    let channel = |i: usize| channels[i].as_u64().and_then(|c| u8::try_from(c).ok());
+    Some(Color::Rgb { r: channel(0)?, g: channel(1)?, b: channel(2)? })
+}
@@ -0,0 +1,168 @@
+use crate::game::{GameState, PileType, PositionKey};
+use crate::moves::{auto_complete_step, hint_moves, Move};
+use std::collections::HashSet;
+
+// Positions examined before a deal is written off as undecided
+pub const DEFAULT_NODE_BUDGET: usize = 20_000;
+
+// Enough to settle a short endgame, and cheap enough to try after every move
+pub const TRIVIAL_WIN_BUDGET: usize = 300;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SolveResult {
+    Winnable,
+    Unwinnable,
+    Undecided, // Ran out of budget before finding an answer
+}
+
+pub fn solve(game: &GameState, node_budget: usize) -> SolveResult {
+    match search(game, node_budget) {
+        Ok(_) => SolveResult::Winnable,
+        Err(result) => result,
+    }
+}
+
+// Moves, draws included, in the first win the solver finds. Not the shortest
+// possible, but the same for every play of a deal, so it makes a fair par.
+pub fn par(game: &GameState, node_budget: usize) -> Option<u32> {
+    search(game, node_budget).ok().map(|won| won.move_count - game.move_count)
+}
+
+// Depth-first search over every legal move, drawing last. Positions already
+// seen are skipped, which also stops the stock from being cycled forever.
+// Returns the won position, or why there isn't one.
+fn search(game: &GameState, node_budget: usize) -> Result<GameState, SolveResult> {
+    let mut start = game.clone();
+    start.undo_stack.clear();
+    start.redo_stack.clear();
+    start.history.clear(); // Nor part of the game's own line of play
+    start.selected_card = None;
+    start.clear_event_hook(); // Explored positions aren't real moves
+    start.clear_hint();
+    // Turning a card over as soon as it can be never hurts, so the search flips as it goes
+    start.rules.auto_flip = true;
+    for column in &mut start.tableau {
+        if let Some(top) = column.last_mut() {
+            top.face_up = true;
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let mut pending = vec![start];
+    let mut nodes = 0;
+
+    while let Some(state) = pending.pop() {
+        if state.is_won() {
+            return Ok(state);
+        }
+        if !seen.insert(position_key(&state)) {
+            continue;
+        }
+        nodes += 1;
+        if nodes > node_budget {
+            return Err(SolveResult::Undecided);
+        }
+
+        // An Ace or Two can always go up straight away, so don't branch on it
+        let candidates = hint_moves(&state);
+        if let Some(safe) = candidates.iter().find(|mv| is_safe_foundation_move(mv)) {
+            pending.push(apply(&state, safe.clone()));
+            continue;
+        }
+
+        // Pushed worst first so the best candidate is explored next
+        if let Some(drawn) = draw(&state) {
+            pending.push(drawn);
+        }
+        for mv in candidates.into_iter().rev() {
+            if is_productive(&state, &mv) {
+                pending.push(apply(&state, mv));
+            }
+        }
+    }
+
+    Err(SolveResult::Unwinnable)
+}
+
+// The first move of a win from here, found by trying each candidate in turn
+pub fn winning_move(game: &GameState, node_budget: usize) -> Option<Move> {
+    let mut base = game.clone();
+    base.undo_stack.clear();
+    base.redo_stack.clear();
+    hint_moves(&base).into_iter()
+        .filter(|mv| is_productive(&base, mv))
+        .find(|mv| solve(&apply(&base, mv.clone()), node_budget) == SolveResult::Winnable)
+}
+
+// One step of finishing a game is_trivially_won says is won: turn over a
+// face-down card, or else play the next card of the win. Once every card is
+// face up it is plain auto-complete. Returns false when there is nothing to do.
+pub fn finish_step(game: &mut GameState) -> bool {
+    if let Some(col) = game.face_down_top() {
+        return game.flip_top(col);
+    }
+    if game.can_auto_complete() {
+        return auto_complete_step(game);
+    }
+    match winning_move(game, TRIVIAL_WIN_BUDGET) {
+        Some(mut mv) => mv.execute(game),
+        None => false,
+    }
+}
+
+// Finishes such a game in one go
+pub fn finish(game: &mut GameState) -> bool {
+    let mut moves_made = false;
+    while finish_step(game) {
+        moves_made = true;
+    }
+    moves_made
+}
+
+fn apply(state: &GameState, mut mv: Move) -> GameState {
+    let mut next = state.clone();
+    mv.execute(&mut next);
+    next.undo_stack.clear();
+    next
+}
+
+fn is_safe_foundation_move(mv: &Move) -> bool {
+    mv.to.pile_type == PileType::Foundation && mv.cards.first().is_some_and(|card| card.rank as u8 <= 2)
+}
+
+// Shuffling a run between two face-up cards only matters if it uncovers
+// something: a face-down card, an empty column, or a card for the foundations
+fn is_productive(state: &GameState, mv: &Move) -> bool {
+    if mv.from.pile_type != PileType::Tableau || mv.to.pile_type != PileType::Tableau {
+        return true;
+    }
+    match mv.from.card_index.checked_sub(1).map(|below| state.tableau[mv.from.pile_index][below]) {
+        None => true,
+        Some(card) if !card.face_up => true,
+        Some(card) => (0..state.foundations.len()).any(|f| state.is_valid_foundation_move(&card, f)),
+    }
+}
+
+fn draw(state: &GameState) -> Option<GameState> {
+    if state.stock.is_empty() && (state.waste.is_empty() || !state.can_recycle()) {
+        return None;
+    }
+    let mut next = state.clone();
+    next.draw_from_stock();
+    next.undo_stack.clear();
+    Some(next)
+}
+
+// Everything that affects which moves are possible: the cards, plus the passes
+// used when they are limited, since the same layout with fewer passes left is worse
+fn position_key(state: &GameState) -> (PositionKey, Option<u32>) {
+    (state.position_key(), state.rules.max_passes.map(|_| state.recycles))
+}
+
+// The first seed from `seed` onwards whose deal the solver can finish
+pub fn find_winnable_seed(make_game: impl Fn(u64) -> GameState, seed: u64, attempts: u32) -> Option<u64> {
+    (0..attempts as u64)
+        .map(|offset| seed.wrapping_add(offset))
+        .find(|&candidate| solve(&make_game(candidate), DEFAULT_NODE_BUDGET) == SolveResult::Winnable)
+}
+
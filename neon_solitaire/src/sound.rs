@@ -0,0 +1,35 @@
+use std::io::{stdout, Write};
+use std::thread;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BellEvent {
+    Move,
+    IllegalMove,
+    Foundation,
+    Win,
+}
+
+impl BellEvent {
+    fn pattern(&self) -> &'static [u64] {
+        // Gaps (in ms) before each extra ring, so events sound distinct
+        match self {
+            BellEvent::Move => &[],
+            BellEvent::IllegalMove => &[60],
+            BellEvent::Foundation => &[150],
+            BellEvent::Win => &[150, 150, 150],
+        }
+    }
+}
+
+pub fn ring_bell(event: BellEvent) -> std::io::Result<()> {
+    let mut out = stdout();
+    out.write_all(b"\x07")?;
+    out.flush()?;
+    for &gap in event.pattern() {
+        thread::sleep(Duration::from_millis(gap));
+        out.write_all(b"\x07")?;
+        out.flush()?;
+    }
+    Ok(())
+}
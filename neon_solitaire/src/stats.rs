@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    pub games_played: u32,
+    pub games_won: u32,
+    pub best_score: i32,
+    pub best_time_secs: Option<u64>, // Fastest win
+    pub current_streak: u32,         // Consecutive wins
+    pub longest_streak: u32,
+    pub peeked_games: u32,           // Games where face-down cards were peeked at
+}
+
+impl Stats {
+    // A missing or unreadable file just means no games have been recorded yet
+    pub fn load<P: AsRef<Path>>(path: P) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let text = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, text)
+    }
+
+    pub fn record_game(&mut self, won: bool, score: i32, time_secs: u64) {
+        self.games_played += 1;
+        self.best_score = self.best_score.max(score);
+
+        if won {
+            self.games_won += 1;
+            self.current_streak += 1;
+            self.longest_streak = self.longest_streak.max(self.current_streak);
+            self.best_time_secs = Some(self.best_time_secs.map_or(time_secs, |best| best.min(time_secs)));
+        } else {
+            self.current_streak = 0;
+        }
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        if self.games_played == 0 {
+            0.0
+        } else {
+            self.games_won as f64 * 100.0 / self.games_played as f64
+        }
+    }
+}
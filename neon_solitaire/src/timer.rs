@@ -0,0 +1,39 @@
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy)]
+pub struct GameTimer {
+    started: Instant,
+    paused_at: Option<Instant>,   // Set while the clock is stopped
+    paused_total: Duration,       // Time spent paused so far
+}
+
+impl GameTimer {
+    pub fn start() -> Self {
+        GameTimer {
+            started: Instant::now(),
+            paused_at: None,
+            paused_total: Duration::ZERO,
+        }
+    }
+
+    pub fn pause(&mut self) {
+        if self.paused_at.is_none() {
+            self.paused_at = Some(Instant::now());
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if let Some(paused_at) = self.paused_at.take() {
+            self.paused_total += paused_at.elapsed();
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        let now = self.paused_at.unwrap_or_else(Instant::now);
+        now.duration_since(self.started).saturating_sub(self.paused_total)
+    }
+}
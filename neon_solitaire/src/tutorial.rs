@@ -0,0 +1,114 @@
+use crate::card::{Card, Rank, Suit, create_standard_deck};
+use crate::game::{GameState, PileType};
+use crate::input::InputAction;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TutorialStep {
+    Draw,
+    SelectWaste,
+    MoveToTableau,
+    MoveToFoundation,
+    Done,
+}
+
+pub struct Tutorial {
+    pub step: TutorialStep,
+}
+
+// The scripted cards: the first draw is the 8♣, which fits on the 9♥ in
+// column 2, and the A♠ waits on top of column 1
+const DRAWN: (Suit, Rank) = (Suit::Clubs, Rank::Eight);
+const TARGET: (Suit, Rank) = (Suit::Hearts, Rank::Nine);
+const ACE: (Suit, Rank) = (Suit::Spades, Rank::Ace);
+
+impl Default for Tutorial {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tutorial {
+    pub fn new() -> Self {
+        Tutorial {
+            step: TutorialStep::Draw,
+        }
+    }
+
+    pub fn create_game() -> GameState {
+        let is = |card: &Card, (suit, rank): (Suit, Rank)| card.suit == suit && card.rank == rank;
+
+        let mut rest: Vec<Card> = create_standard_deck()
+            .into_iter()
+            .filter(|c| !is(c, DRAWN) && !is(c, TARGET) && !is(c, ACE))
+            .collect();
+
+        // Column tops are dealt from indices 0, 2, 5, ... and the stock is drawn from the end
+        let mut deck = Vec::with_capacity(52);
+        deck.push(Card::new(ACE.0, ACE.1));
+        deck.push(rest.remove(0));
+        deck.push(Card::new(TARGET.0, TARGET.1));
+        deck.append(&mut rest);
+        deck.push(Card::new(DRAWN.0, DRAWN.1));
+
+        let mut game = GameState::deal(deck, 0);
+        game.draw_count = 1;
+        game
+    }
+
+    pub fn prompt(&self) -> &'static str {
+        match self.step {
+            TutorialStep::Draw => "Step 1/4: Press SPACE (or click the stock) to draw a card",
+            TutorialStep::SelectWaste => "Step 2/4: Press W (or click the waste) to pick up the 8♣",
+            TutorialStep::MoveToTableau => "Step 3/4: Press 2 (or click column 2) to put the 8♣ on the 9♥",
+            TutorialStep::MoveToFoundation => "Step 4/4: Press A to send the A♠ to its foundation",
+            TutorialStep::Done => "Tutorial complete! Press any key to start a real game",
+        }
+    }
+
+    // Only the actions the current step teaches get through, plus the always-safe ones
+    pub fn allows(&self, action: &InputAction) -> bool {
+        match action {
+            InputAction::None
+            | InputAction::Quit
+            | InputAction::QuitNow
+            | InputAction::Pause
+            | InputAction::Undo
+            | InputAction::KeyReference
+            | InputAction::MouseClick(..) => true,
+            _ => matches!(
+                (self.step, action),
+                (TutorialStep::Draw, InputAction::DrawFromStock)
+                    | (TutorialStep::SelectWaste, InputAction::SelectWaste)
+                    | (TutorialStep::MoveToTableau, InputAction::SelectColumn(1))
+                    | (TutorialStep::MoveToTableau, InputAction::SelectWaste)
+                    | (TutorialStep::MoveToFoundation, InputAction::AutoMove)
+                    | (TutorialStep::MoveToFoundation, InputAction::MouseRightClick(..))
+            ),
+        }
+    }
+
+    // Moves on once the board shows the current step was performed; returns true if it did
+    pub fn advance(&mut self, game: &GameState) -> bool {
+        let completed = match self.step {
+            TutorialStep::Draw => !game.waste.is_empty(),
+            TutorialStep::SelectWaste => {
+                matches!(game.selected_card, Some((PileType::Waste, _, _)))
+            }
+            TutorialStep::MoveToTableau => game.tableau[1]
+                .last()
+                .is_some_and(|c| c.suit == DRAWN.0 && c.rank == DRAWN.1),
+            TutorialStep::MoveToFoundation => game.foundations.iter().any(|f| !f.is_empty()),
+            TutorialStep::Done => false,
+        };
+
+        if completed {
+            self.step = match self.step {
+                TutorialStep::Draw => TutorialStep::SelectWaste,
+                TutorialStep::SelectWaste => TutorialStep::MoveToTableau,
+                TutorialStep::MoveToTableau => TutorialStep::MoveToFoundation,
+                TutorialStep::MoveToFoundation | TutorialStep::Done => TutorialStep::Done,
+            };
+        }
+        completed
+    }
+}